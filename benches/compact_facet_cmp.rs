@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compare the derived `PartialEq`/`Ord` of [`CompactFacet`] against
+//! comparing the byte slices directly.
+//!
+//! Run with `cargo bench --bench compact_facet_cmp`.
+
+use std::{cmp::Ordering, hint::black_box, time::Instant};
+
+use gigtags::facet::{CompactFacet, FacetBuild as _};
+
+const NUM_ITERATIONS: usize = 1_000_000;
+
+fn eq_bytes(lhs: &CompactFacet, rhs: &CompactFacet) -> bool {
+    lhs.as_bytes() == rhs.as_bytes()
+}
+
+fn cmp_bytes(lhs: &CompactFacet, rhs: &CompactFacet) -> Ordering {
+    lhs.as_bytes().cmp(rhs.as_bytes())
+}
+
+fn main() {
+    let pairs = [
+        // Inline
+        ("genre", "genre"),
+        ("genre/house", "genre/techno"),
+        ("played@20220625", "played@20220626"),
+        // Heap-allocated
+        (
+            "genre/electronic/house/deep@20220625",
+            "genre/electronic/house/deep@20220626",
+        ),
+    ]
+    .map(|(lhs, rhs)| (CompactFacet::from_str(lhs), CompactFacet::from_str(rhs)));
+
+    for (lhs, rhs) in &pairs {
+        assert_eq!(lhs == rhs, eq_bytes(lhs, rhs));
+        assert_eq!(lhs.cmp(rhs), cmp_bytes(lhs, rhs));
+    }
+
+    for (lhs, rhs) in &pairs {
+        let started = Instant::now();
+        for _ in 0..NUM_ITERATIONS {
+            black_box(black_box(lhs) == black_box(rhs));
+            black_box(black_box(lhs).cmp(black_box(rhs)));
+        }
+        let derived_elapsed = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..NUM_ITERATIONS {
+            black_box(eq_bytes(black_box(lhs), black_box(rhs)));
+            black_box(cmp_bytes(black_box(lhs), black_box(rhs)));
+        }
+        let bytes_elapsed = started.elapsed();
+
+        println!(
+            "{len} bytes: derived {derived_elapsed:?} / bytes {bytes_elapsed:?}",
+            len = lhs.len()
+        );
+    }
+}
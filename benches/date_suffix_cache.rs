@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compare the [`DateSuffixCache`] against parsing date suffixes repeatedly.
+//!
+//! Run with `cargo bench --bench date_suffix_cache`.
+
+use std::{hint::black_box, time::Instant};
+
+use gigtags::facet::{date_suffix, DateSuffixCache};
+
+const NUM_FACETS: usize = 100;
+
+const NUM_ITERATIONS: usize = 10_000;
+
+fn main() {
+    let facets = (0..NUM_FACETS)
+        .map(|i| format!("category{}/played@202206{:02}", i % 10, i % 30 + 1))
+        .collect::<Vec<_>>();
+    let mut cache = DateSuffixCache::new(NUM_FACETS);
+
+    for facet in &facets {
+        assert_eq!(date_suffix(facet), cache.get_or_parse(facet));
+    }
+
+    let started = Instant::now();
+    for _ in 0..NUM_ITERATIONS {
+        for facet in &facets {
+            black_box(date_suffix(black_box(facet)));
+        }
+    }
+    let uncached_elapsed = started.elapsed();
+
+    let started = Instant::now();
+    for _ in 0..NUM_ITERATIONS {
+        for facet in &facets {
+            black_box(cache.get_or_parse(black_box(facet)));
+        }
+    }
+    let cached_elapsed = started.elapsed();
+
+    println!("uncached: {uncached_elapsed:?}");
+    println!("cached:   {cached_elapsed:?}");
+}
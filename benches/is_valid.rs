@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compare [`is_valid()`] against a naive implementation that trims the facet.
+//!
+//! Run with `cargo bench --bench is_valid`.
+
+use std::{hint::black_box, time::Instant};
+
+use gigtags::facet::is_valid;
+
+const NUM_ITERATIONS: usize = 1_000_000;
+
+fn is_valid_trim(facet: &str) -> bool {
+    facet.trim() == facet && facet.as_bytes().first() != Some(&b'/')
+}
+
+fn main() {
+    let facets = [
+        "genre".to_owned(),
+        "genre/electronic/house@20220625".to_owned(),
+        "genre/electronic/house/".repeat(100),
+    ];
+
+    for facet in &facets {
+        assert_eq!(is_valid_trim(facet), is_valid(facet));
+    }
+
+    for facet in &facets {
+        let started = Instant::now();
+        for _ in 0..NUM_ITERATIONS {
+            black_box(is_valid_trim(black_box(facet)));
+        }
+        let trim_elapsed = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..NUM_ITERATIONS {
+            black_box(is_valid(black_box(facet)));
+        }
+        let elapsed = started.elapsed();
+
+        println!(
+            "{len} bytes: trim {trim_elapsed:?} / is_valid {elapsed:?}",
+            len = facet.len()
+        );
+    }
+}
@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compare the [`PrefixMatcher`] against naively scanning all prefixes.
+//!
+//! Run with `cargo bench --bench prefix_matcher`.
+
+use std::{hint::black_box, time::Instant};
+
+use gigtags::facet::{starts_with_path_prefix, PrefixMatcher};
+
+const NUM_PREFIXES: usize = 500;
+
+const NUM_ITERATIONS: usize = 100;
+
+fn naive_longest_match<'a>(prefixes: &'a [String], facet: &str) -> Option<&'a str> {
+    prefixes
+        .iter()
+        .filter(|prefix| starts_with_path_prefix(facet, prefix))
+        .max_by_key(|prefix| prefix.len())
+        .map(String::as_str)
+}
+
+fn main() {
+    let prefixes = (0..NUM_PREFIXES)
+        .map(|i| format!("category{}/sub{}", i % 50, i / 50))
+        .collect::<Vec<_>>();
+    let facets = (0..NUM_PREFIXES)
+        .map(|i| format!("category{}/sub{}/leaf{i}@20220625", i % 50, i % 20))
+        .collect::<Vec<_>>();
+    let matcher = PrefixMatcher::new(prefixes.iter().map(String::as_str));
+
+    for facet in &facets {
+        assert_eq!(
+            naive_longest_match(&prefixes, facet),
+            matcher.longest_match(facet)
+        );
+    }
+
+    let started = Instant::now();
+    for _ in 0..NUM_ITERATIONS {
+        for facet in &facets {
+            black_box(naive_longest_match(&prefixes, black_box(facet)));
+        }
+    }
+    let naive_elapsed = started.elapsed();
+
+    let started = Instant::now();
+    for _ in 0..NUM_ITERATIONS {
+        for facet in &facets {
+            black_box(matcher.longest_match(black_box(facet)));
+        }
+    }
+    let matcher_elapsed = started.elapsed();
+
+    println!("naive scan:     {naive_elapsed:?}");
+    println!("prefix matcher: {matcher_elapsed:?}");
+}
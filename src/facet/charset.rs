@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+/// A compact allow-list of characters
+///
+/// ASCII characters are stored in a bitmap. All non-ASCII characters
+/// are either allowed or rejected together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CharSet {
+    ascii: u128,
+    non_ascii: bool,
+}
+
+impl CharSet {
+    /// Create an empty set that doesn't contain any characters.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ascii: 0,
+            non_ascii: false,
+        }
+    }
+
+    /// Add an inclusive range of ASCII characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `last` is not an ASCII character.
+    #[must_use]
+    pub const fn with_ascii_range(mut self, first: u8, last: u8) -> Self {
+        assert!(last.is_ascii());
+        let mut next = first;
+        while next <= last {
+            self.ascii |= 1 << next;
+            next += 1;
+        }
+        self
+    }
+
+    /// Add ASCII characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the given characters is not an ASCII character.
+    #[must_use]
+    pub const fn with_ascii_chars(mut self, chars: &[u8]) -> Self {
+        let mut index = 0;
+        while index < chars.len() {
+            let next = chars[index];
+            assert!(next.is_ascii());
+            self.ascii |= 1 << next;
+            index += 1;
+        }
+        self
+    }
+
+    /// Allow or reject all non-ASCII characters.
+    #[must_use]
+    pub const fn with_non_ascii(mut self, allowed: bool) -> Self {
+        self.non_ascii = allowed;
+        self
+    }
+
+    /// Check if the set contains an ASCII character, given as a single byte.
+    ///
+    /// Returns `false` for all non-ASCII bytes.
+    #[must_use]
+    pub const fn contains_ascii(&self, byte: u8) -> bool {
+        byte.is_ascii() && self.ascii & (1 << byte) != 0
+    }
+
+    /// Check if the set contains a character.
+    #[must_use]
+    pub const fn contains(&self, ch: char) -> bool {
+        if ch.is_ascii() {
+            #[allow(clippy::cast_possible_truncation)]
+            self.contains_ascii(ch as u8)
+        } else {
+            self.non_ascii
+        }
+    }
+
+    /// Check if the set contains all characters of a string.
+    #[must_use]
+    pub fn contains_all(&self, s: &str) -> bool {
+        if s.is_ascii() {
+            // Fast path
+            return s.bytes().all(|byte| self.contains_ascii(byte));
+        }
+        s.chars().all(|ch| self.contains(ch))
+    }
+}
@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use time::Date;
+
+use super::date_suffix;
+
+/// Memoize parsed date suffixes of facets
+///
+/// The number of cached entries is bounded. The least recently
+/// used entry is evicted when inserting a new entry into a full
+/// cache.
+///
+/// Both lookups and evictions take amortized constant time.
+#[derive(Debug)]
+pub struct DateSuffixCache {
+    capacity: usize,
+    access_count: u64,
+    entries: HashMap<Arc<str>, (Option<Date>, u64)>,
+    /// Accessed keys in chronological order, tagged with the access count.
+    ///
+    /// Accessing a key again leaves a stale item behind that is skipped
+    /// during eviction.
+    access_order: VecDeque<(Arc<str>, u64)>,
+}
+
+impl DateSuffixCache {
+    /// Create an empty cache with the given maximum number of entries.
+    ///
+    /// A capacity of zero is treated as a capacity of one.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            access_count: 0,
+            entries: HashMap::with_capacity(capacity),
+            access_order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// The maximum number of entries.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The current number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check if the date suffix of the facet is cached.
+    ///
+    /// Does not count as an access.
+    #[must_use]
+    pub fn contains(&self, facet: &str) -> bool {
+        self.entries.contains_key(facet)
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.access_order.clear();
+    }
+
+    /// Get the cached [`date_suffix()`] of the facet or parse and cache it.
+    pub fn get_or_parse(&mut self, facet: &str) -> Option<Date> {
+        self.access_count += 1;
+        let access_count = self.access_count;
+        if let Some((key, &(date, _))) = self.entries.get_key_value(facet) {
+            let key = Arc::clone(key);
+            self.entries.insert(Arc::clone(&key), (date, access_count));
+            self.push_access(key);
+            return date;
+        }
+        if self.entries.len() >= self.capacity {
+            self.evict_least_recently_used();
+        }
+        let date = date_suffix(facet);
+        let key: Arc<str> = facet.into();
+        self.entries.insert(Arc::clone(&key), (date, access_count));
+        self.push_access(key);
+        date
+    }
+
+    fn push_access(&mut self, key: Arc<str>) {
+        self.access_order.push_back((key, self.access_count));
+        if self.access_order.len() > 2 * self.capacity {
+            // Discard stale items. At least half of the items are stale,
+            // which amortizes the costs.
+            let entries = &self.entries;
+            self.access_order.retain(|(key, access_count)| {
+                entries.get(key).map(|(_, last_access)| *last_access) == Some(*access_count)
+            });
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        while let Some((key, access_count)) = self.access_order.pop_front() {
+            if self.entries.get(&key).map(|(_, last_access)| *last_access) == Some(access_count) {
+                self.entries.remove(&key);
+                return;
+            }
+        }
+    }
+}
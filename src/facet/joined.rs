@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! (De-)serialize facets as a single string
+//!
+//! Intended for `#[serde(with = "gigtags::facet::joined")]` on fields
+//! of type `Vec<CompactFacet>`.
+
+use std::borrow::Cow;
+
+use super::{validate, CompactFacet, FacetBuild as _};
+
+/// Delimiter of the joined facets.
+pub const DELIMITER: &str = " ";
+
+/// Serialize facets as a single string, joined by [`DELIMITER`].
+///
+/// # Errors
+///
+/// Fails for empty facets and for facets that contain the delimiter,
+/// because they could not be deserialized again.
+pub fn serialize<S>(facets: &[CompactFacet], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::Error as _;
+    if let Some(facet) = facets
+        .iter()
+        .find(|facet| facet.is_empty() || facet.contains(DELIMITER))
+    {
+        return Err(S::Error::custom(format_args!(
+            "facet {facet:?} cannot be joined",
+            facet = &**facet
+        )));
+    }
+    let joined = facets.iter().map(|facet| &**facet).collect::<Vec<_>>();
+    serializer.serialize_str(&joined.join(DELIMITER))
+}
+
+/// Deserialize facets from a single string, joined by [`DELIMITER`].
+///
+/// Consecutive delimiters are treated as a single delimiter.
+///
+/// # Errors
+///
+/// Fails if any of the facets is invalid.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<CompactFacet>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error as _;
+    let joined = <Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+    joined
+        .split(DELIMITER)
+        .filter(|facet| !facet.is_empty())
+        .map(|facet| {
+            validate(facet)
+                .map(|()| CompactFacet::from_str(facet))
+                .map_err(|err| D::Error::custom(format_args!("invalid facet {facet:?}: {err}")))
+        })
+        .collect()
+}
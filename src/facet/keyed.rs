@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::hash::{Hash, Hasher};
+
+use super::{stable_hash, FacetRef};
+
+/// A facet together with its precomputed, stable hash
+///
+/// Intended for comparing the same facets repeatedly, e.g. when diffing
+/// lists in a UI. Equality first compares the cached hashes and only
+/// compares the bytes of both facets if the hashes match.
+#[derive(Debug, Clone)]
+pub struct Keyed<F> {
+    key: u32,
+    facet: F,
+}
+
+impl<F> Keyed<F>
+where
+    F: FacetRef,
+{
+    /// Wrap a facet and compute its hash.
+    #[must_use]
+    pub fn new(facet: F) -> Self {
+        let key = stable_hash(facet.as_ref().as_bytes());
+        Self { key, facet }
+    }
+
+    /// The precomputed, stable hash of the facet.
+    #[must_use]
+    pub const fn key(&self) -> u32 {
+        self.key
+    }
+
+    /// The wrapped facet.
+    #[must_use]
+    pub const fn facet(&self) -> &F {
+        &self.facet
+    }
+
+    /// Unwrap the facet.
+    #[must_use]
+    pub fn into_inner(self) -> F {
+        let Self { key: _, facet } = self;
+        facet
+    }
+}
+
+impl<F> PartialEq for Keyed<F>
+where
+    F: FacetRef,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.facet.as_ref() == other.facet.as_ref()
+    }
+}
+
+impl<F> Eq for Keyed<F> where F: FacetRef {}
+
+impl<F> Hash for Keyed<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(self.key);
+    }
+}
@@ -3,11 +3,21 @@
 
 //! Facets
 
-use std::{borrow::Cow, fmt, ops::Deref};
+use std::{
+    borrow::{Borrow, Cow},
+    cmp::Ordering,
+    collections::BTreeMap,
+    ffi::OsStr,
+    fmt,
+    ops::{Deref, Range},
+    str::Utf8Error,
+};
 
+#[cfg(feature = "compact_str")]
 use compact_str::{format_compact, CompactString};
 use once_cell::sync::OnceCell;
 use regex::bytes::Regex;
+use thiserror::Error;
 use time::{format_description::FormatItem, macros::format_description, Date};
 
 /// Check if the given facet is valid.
@@ -15,85 +25,2405 @@ use time::{format_description::FormatItem, macros::format_description, Date};
 /// An empty facet is valid.
 #[must_use]
 pub fn is_valid(facet: &str) -> bool {
-    facet.trim() == facet && facet.as_bytes().first() != Some(&b'/')
+    // Only the first and the last character need to be inspected
+    // instead of comparing the facet with its trimmed slice.
+    let mut chars = facet.chars();
+    let Some(first) = chars.next() else {
+        return true;
+    };
+    if first == PATH_SEPARATOR || first.is_whitespace() {
+        return false;
+    }
+    !chars.next_back().is_some_and(char::is_whitespace)
+}
+
+/// Check if the given facet is valid, permitting a single leading slash.
+///
+/// A leading slash `/` marks an _absolute_ facet, i.e. the facet without the
+/// leading slash must be [`is_valid()`]. Facets without a leading slash are
+/// considered as _relative_.
+///
+/// Absolute facets are not supported by the canonical [`is_valid()`] for
+/// backwards compatibility and could not be encoded in tags.
+#[must_use]
+pub fn is_valid_allowing_leading_slash(facet: &str) -> bool {
+    is_valid(facet.strip_prefix(PATH_SEPARATOR).unwrap_or(facet))
+}
+
+/// Check if the given facet is absolute, i.e. starts with a leading slash.
+///
+/// See also: [`is_valid_allowing_leading_slash()`]
+#[must_use]
+pub fn is_absolute(facet: &str) -> bool {
+    debug_assert!(is_valid_allowing_leading_slash(facet));
+    facet.starts_with(PATH_SEPARATOR)
+}
+
+/// Check if the given facet is valid and not only a date-like suffix.
+///
+/// In contrast to [`is_valid()`] facets that only consist of a date-like
+/// suffix with an empty prefix like `@20220625` are rejected.
+#[must_use]
+pub fn is_valid_non_dateonly(facet: &str) -> bool {
+    is_valid(facet) && !(facet.len() == DATE_LIKE_SUFFIX_LEN && has_date_like_suffix(facet))
+}
+
+/// Reasons why a facet is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum FacetError {
+    /// Leading or trailing whitespace.
+    #[error("leading or trailing whitespace")]
+    LeadingOrTrailingWhitespace,
+
+    /// Leading slash `/`.
+    #[error("leading slash")]
+    LeadingSlash,
+
+    /// Whitespace before a date-like suffix.
+    #[error("whitespace before date-like suffix")]
+    InvalidDateLikeSuffix,
+}
+
+/// Reasons why raw bytes could not be converted into a facet
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum FacetFromBytesError {
+    /// Invalid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] Utf8Error),
+
+    /// Invalid facet.
+    #[error(transparent)]
+    InvalidFacet(#[from] FacetError),
+}
+
+/// Reasons why a CSV field could not be converted into a facet
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum FacetFromCsvError {
+    /// Unbalanced or missing quotes.
+    #[error("invalid quoting")]
+    InvalidQuoting,
+
+    /// Invalid facet.
+    #[error(transparent)]
+    InvalidFacet(#[from] FacetError),
+}
+
+/// Decode the bytes of a key or value in a URL query string.
+///
+/// A `+` is decoded as a space before percent-decoding the remaining bytes.
+#[cfg(feature = "compact_str")]
+fn decode_query_component(encoded: &str) -> impl Iterator<Item = u8> + '_ {
+    encoded.split('+').enumerate().flat_map(|(index, encoded)| {
+        (index > 0)
+            .then_some(b' ')
+            .into_iter()
+            .chain(percent_encoding::percent_decode_str(encoded))
+    })
+}
+
+/// Reasons why two facets could not be joined
+///
+/// See also: [`join()`]
+#[cfg(feature = "compact_str")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum JoinError {
+    /// Invalid base or relative facet.
+    #[error(transparent)]
+    InvalidFacet(#[from] FacetError),
+
+    /// Date-like suffix in the base facet.
+    ///
+    /// Dated facets are leaves that cannot be nested any further.
+    #[error("date-like suffix in base")]
+    DatedBase,
+}
+
+/// Reasons why a facet could not be decoded from its binary representation
+///
+/// See also: [`decode_binary()`]
+#[cfg(feature = "compact_str")]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BinaryDecodeError {
+    /// The input ended prematurely.
+    #[error("truncated input")]
+    Truncated,
+
+    /// The length prefix doesn't fit into `usize`.
+    #[error("length overflow")]
+    LengthOverflow,
+
+    /// Invalid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] Utf8Error),
+
+    /// Invalid facet.
+    #[error(transparent)]
+    InvalidFacet(#[from] FacetError),
+}
+
+/// Reasons why an environment variable could not be read as a facet
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum FacetFromEnvError {
+    /// The value is not valid Unicode.
+    #[error("not unicode: {0:?}")]
+    NotUnicode(std::ffi::OsString),
+
+    /// The value is not a valid facet.
+    #[error("invalid facet {value:?}")]
+    InvalidFacet {
+        /// The raw value.
+        value: String,
+
+        /// The reason why the value is invalid.
+        #[source]
+        error: FacetError,
+    },
+}
+
+/// Reasons why a facet with a date suffix could not be created
+#[derive(Debug, Error)]
+pub enum DatedFacetError {
+    /// Invalid prefix.
+    #[error("invalid prefix: {0}")]
+    InvalidPrefix(FacetError),
+
+    /// Invalid facet after rendering a template.
+    #[error("invalid rendered facet: {0}")]
+    InvalidRendered(FacetError),
+
+    /// Invalid calendar date.
+    #[error("invalid date: {0}")]
+    InvalidDate(time::error::ComponentRange),
+
+    /// The date could not be encoded as a date suffix.
+    #[error(transparent)]
+    Format(#[from] time::error::Format),
+}
+
+/// Validate the given facet.
+///
+/// In contrast to [`is_valid()`] facets with an invalid date-like suffix
+/// are also rejected, i.e. when the `@` of the suffix is preceded by
+/// whitespace.
+///
+/// # Errors
+///
+/// Returns a [`FacetError`] if the facet is invalid.
+pub fn validate(facet: &str) -> Result<(), FacetError> {
+    if facet.trim() != facet {
+        return Err(FacetError::LeadingOrTrailingWhitespace);
+    }
+    if facet.as_bytes().first() == Some(&b'/') {
+        return Err(FacetError::LeadingSlash);
+    }
+    if has_invalid_date_like_suffix(facet) {
+        return Err(FacetError::InvalidDateLikeSuffix);
+    }
+    Ok(())
+}
+
+/// [Validate](validate()) many facets in parallel.
+///
+/// The results are returned in the same order as the inputs.
+#[cfg(feature = "rayon")]
+#[must_use]
+pub fn validate_par<S: AsRef<str> + Sync>(inputs: &[S]) -> Vec<Result<(), FacetError>> {
+    use rayon::prelude::*;
+    inputs
+        .par_iter()
+        .map(|input| validate(input.as_ref()))
+        .collect()
+}
+
+/// Check if the given facet is empty.
+#[must_use]
+pub fn is_empty(facet: &str) -> bool {
+    debug_assert!(is_valid(facet));
+    facet.is_empty()
+}
+
+/// Check for a date-like suffix in the facet.
+#[must_use]
+pub fn has_date_like_suffix(facet: &str) -> bool {
+    debug_assert!(is_valid(facet));
+    date_like_suffix_regex().is_match(facet.as_bytes())
+}
+
+/// Split a string into a prefix and a suffix of the given length in bytes
+/// that starts with the separator `sep`.
+///
+/// The length of the suffix includes the separator. Returns `None` if the
+/// string is too short, if the split would not be at a char boundary, or
+/// if the suffix doesn't start with the separator.
+#[must_use]
+pub fn try_split_suffix(facet: &str, suffix_len: usize, sep: char) -> Option<(&str, &str)> {
+    let (prefix, suffix) = try_split_off_suffix_len(facet, suffix_len)?;
+    suffix.starts_with(sep).then_some((prefix, suffix))
+}
+
+/// Split a string into a prefix and a suffix of the given length in bytes.
+///
+/// Returns `None` if the string is too short or if the split would not be
+/// at a char boundary.
+fn try_split_off_suffix_len(facet: &str, suffix_len: usize) -> Option<(&str, &str)> {
+    let prefix_len = facet.len().checked_sub(suffix_len)?;
+    facet
+        .is_char_boundary(prefix_len)
+        .then(|| facet.split_at(prefix_len))
+}
+
+/// Split a facet into a prefix and the date-like suffix.
+///
+/// Returns `None` if the last 9 bytes don't start
+/// with `@`. The digits are not checked, see [`has_date_like_suffix()`].
+#[must_use]
+pub fn try_split_into_prefix_and_date_like_suffix(facet: &str) -> Option<(&str, &str)> {
+    debug_assert!(is_valid(facet));
+    let (prefix, date_suffix) = try_split_suffix(facet, DATE_LIKE_SUFFIX_LEN, '@')?;
+    if !date_suffix.is_ascii() {
+        return None;
+    }
+    (prefix, date_suffix).into()
+}
+
+/// Split a facet into a prefix and the date-like suffix, only if
+/// [`has_date_like_suffix()`].
+fn split_off_date_like_suffix(facet: &str) -> Option<(&str, &str)> {
+    if !has_date_like_suffix(facet) {
+        return None;
+    }
+    try_split_into_prefix_and_date_like_suffix(facet)
+}
+
+/// Get the date-like suffix of a facet, optionally including the leading `@`.
+///
+/// Returns `None` if the facet has no date-like suffix.
+#[must_use]
+pub fn date_like_suffix(facet: &str, include_separator: bool) -> Option<&str> {
+    let (_, suffix) = split_off_date_like_suffix(facet)?;
+    if include_separator {
+        Some(suffix)
+    } else {
+        Some(&suffix[1..])
+    }
+}
+
+/// Split a facet into a prefix and parse the date suffix.
+#[must_use]
+pub fn try_split_into_prefix_and_parse_date_suffix(facet: &str) -> Option<(&str, Option<Date>)> {
+    debug_assert!(is_valid(facet));
+    let (prefix, date_suffix) = try_split_into_prefix_and_date_like_suffix(facet)?;
+    let date = Date::parse(date_suffix, DATE_SUFFIX_FORMAT).ok();
+    (prefix, date).into()
+}
+
+const DATE_SUFFIX_FORMAT: &[FormatItem<'static>] = format_description!("@[year][month][day]");
+
+// @yyyyMMdd
+const DATE_LIKE_SUFFIX_LEN: usize = 1 + 8;
+
+fn format_date_suffix(date: Date) -> Result<String, time::error::Format> {
+    // Negative years or years with more than 4 digits would result
+    // in a suffix that is not date-like.
+    if !(0..=9999).contains(&date.year()) {
+        return Err(time::error::Format::InvalidComponent("year"));
+    }
+    let suffix = date.format(DATE_SUFFIX_FORMAT)?;
+    debug_assert_eq!(DATE_LIKE_SUFFIX_LEN, suffix.len());
+    Ok(suffix)
+}
+
+// @yyyyDDD
+const ORDINAL_DATE_LIKE_SUFFIX_LEN: usize = 1 + 7;
+
+fn format_ordinal_date_suffix(date: Date) -> Result<String, time::error::Format> {
+    // Same restriction of the year as for calendar date suffixes.
+    if !(0..=9999).contains(&date.year()) {
+        return Err(time::error::Format::InvalidComponent("year"));
+    }
+    let suffix = format!("@{:04}{:03}", date.year(), date.ordinal());
+    debug_assert_eq!(ORDINAL_DATE_LIKE_SUFFIX_LEN, suffix.len());
+    Ok(suffix)
+}
+
+/// Check if a facet equals the concatenation of a prefix and a date suffix.
+///
+/// Equivalent to comparing the facet with the result of
+/// [`FacetBuild::from_prefix_with_date_suffix()`], but without
+/// allocating a new facet.
+#[must_use]
+pub fn matches_prefix_and_date(facet: &str, prefix: &str, date: Date) -> bool {
+    let Some(date_suffix) = facet.strip_prefix(prefix) else {
+        return false;
+    };
+    let Some(digits) = date_suffix.strip_prefix('@') else {
+        return false;
+    };
+    let Ok(year) = u16::try_from(date.year()) else {
+        return false;
+    };
+    if year > 9999 || digits.len() != 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    digits[..4].parse() == Ok(year)
+        && digits[4..6].parse() == Ok(u8::from(date.month()))
+        && digits[6..].parse() == Ok(date.day())
+}
+
+/// Split a facet into a prefix and an ordinal date suffix.
+///
+/// The ordinal date suffix consists of a 4-digit year followed by
+/// the 3-digit day of the year, e.g. `@2024366`. It is distinguished
+/// from a calendar date suffix by its length.
+///
+/// Returns `None` if the facet has no ordinal date suffix or if the
+/// suffix does not encode a valid ordinal date.
+#[must_use]
+pub fn try_split_into_prefix_and_ordinal_date(facet: &str) -> Option<(&str, Date)> {
+    debug_assert!(is_valid(facet));
+    let (prefix, suffix) = try_split_suffix(facet, ORDINAL_DATE_LIKE_SUFFIX_LEN, '@')?;
+    let digits = &suffix[1..];
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // The separator must not be preceded by whitespace, see also
+    // the rules for calendar date suffixes.
+    if prefix.chars().next_back().is_some_and(char::is_whitespace) {
+        return None;
+    }
+    let year = digits[..4].parse().ok()?;
+    let ordinal = digits[4..].parse().ok()?;
+    let date = Date::from_ordinal_date(year, ordinal).ok()?;
+    Some((prefix, date))
+}
+
+/// Separators that are accepted by [`try_split_into_prefix_and_date_any_separator()`].
+pub const LENIENT_DATE_SEPARATORS: [char; 4] = ['@', '-', '.', '_'];
+
+const LENIENT_DATE_FORMAT: &[FormatItem<'static>] = format_description!("[year][month][day]");
+
+/// Split a facet into a prefix, a separator, and a date suffix,
+/// accepting any of the [`LENIENT_DATE_SEPARATORS`].
+///
+/// Intended for recovering dates from non-canonical input, e.g.
+/// `event.20220625`, during migrations. Dated facets are otherwise
+/// parsed strictly, see [`try_split_into_prefix_and_parse_date_suffix()`].
+///
+/// Returns `None` if the facet doesn't end with one of the separators
+/// followed by 8 digits that encode a valid calendar date.
+#[must_use]
+pub fn try_split_into_prefix_and_date_any_separator(facet: &str) -> Option<(&str, char, Date)> {
+    LENIENT_DATE_SEPARATORS.into_iter().find_map(|sep| {
+        let (prefix, suffix) = try_split_suffix(facet, DATE_LIKE_SUFFIX_LEN, sep)?;
+        let digits = &suffix[1..];
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let date = Date::parse(digits, LENIENT_DATE_FORMAT).ok()?;
+        Some((prefix, sep, date))
+    })
+}
+
+const LENIENT_ISO_DATE_FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+// yyyy-MM-dd
+const LENIENT_ISO_DATE_LEN: usize = 10;
+
+/// Rewrite a recognizable, non-canonical date suffix into the canonical
+/// form `@yyyyMMdd`, leaving the prefix untouched.
+///
+/// Recognizes the date formats `yyyyMMdd` and `yyyy-MM-dd` after any of
+/// the [`LENIENT_DATE_SEPARATORS`], e.g. `event@2022-06-25` or
+/// `event.20220625` are both rewritten into `event@20220625`.
+///
+/// Intended as a migration tool for non-canonical input. Returns the
+/// facet unmodified if the date suffix is already canonical or if no
+/// date suffix could be recognized.
+#[must_use]
+pub fn canonicalize_date_suffix(facet: &str) -> Cow<'_, str> {
+    if date_suffix(facet).is_some() {
+        return Cow::Borrowed(facet);
+    }
+    let recognized = try_split_into_prefix_and_date_any_separator(facet)
+        .map(|(prefix, _, date)| (prefix, date))
+        .or_else(|| {
+            LENIENT_DATE_SEPARATORS.into_iter().find_map(|sep| {
+                let (prefix, suffix) = try_split_suffix(facet, 1 + LENIENT_ISO_DATE_LEN, sep)?;
+                let date = Date::parse(&suffix[1..], LENIENT_ISO_DATE_FORMAT).ok()?;
+                Some((prefix, date))
+            })
+        });
+    let Some((prefix, date)) = recognized else {
+        return Cow::Borrowed(facet);
+    };
+    let Ok(suffix) = format_date_suffix(date) else {
+        return Cow::Borrowed(facet);
+    };
+    Cow::Owned(format!("{prefix}{suffix}"))
+}
+
+/// Check for any temporal suffix in the facet.
+///
+/// Temporal suffixes are either date-like suffixes, see [`has_date_like_suffix()`],
+/// or ordinal date suffixes, see [`try_split_into_prefix_and_ordinal_date()`].
+#[must_use]
+pub fn has_temporal_suffix(facet: &str) -> bool {
+    has_date_like_suffix(facet) || try_split_into_prefix_and_ordinal_date(facet).is_some()
+}
+
+/// Replace the separator of a date-like suffix.
+///
+/// Only the separator of a trailing suffix that consists of `from`
+/// followed by 8 digits is replaced by `to`. Occurrences of `from`
+/// in the prefix are preserved.
+///
+/// Intended as a migration tool, e.g. for switching from `~` to the
+/// canonical `@`. Returns the facet unmodified if it has no matching
+/// suffix.
+#[must_use]
+pub fn rebase_date_separator(facet: &str, from: char, to: char) -> Cow<'_, str> {
+    if from == to {
+        return Cow::Borrowed(facet);
+    }
+    let Some((prefix, suffix)) = try_split_suffix(facet, from.len_utf8() + 8, from) else {
+        return Cow::Borrowed(facet);
+    };
+    let digits = &suffix[from.len_utf8()..];
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Cow::Borrowed(facet);
+    }
+    Cow::Owned(format!("{prefix}{to}{digits}"))
+}
+
+const DATE_LIKE_SUFFIX_REGEX_STR: &str = r"(^|[^\s])@\d{8}$";
+
+static DATE_LIKE_SUFFIX_REGEX: OnceCell<Regex> = OnceCell::new();
+
+#[must_use]
+fn date_like_suffix_regex() -> &'static Regex {
+    // The '@' separator of the date-like digits must not be preceded by
+    // a whitespace i.e. the facet either equals the date-like suffix
+    // or the separator is preceded by a non-whitespace character.
+    DATE_LIKE_SUFFIX_REGEX.get_or_init(|| DATE_LIKE_SUFFIX_REGEX_STR.parse().unwrap())
+}
+
+const INVALID_DATE_LIKE_SUFFIX_REGEX_STR: &str = r"[\s]+@\d{8}$";
+
+static INVALID_DATE_LIKE_SUFFIX_REGEX: OnceCell<Regex> = OnceCell::new();
+
+#[must_use]
+fn invalid_date_like_suffix_regex() -> &'static Regex {
+    // Reject facets with date-like suffixes that are preceded by a whitespace character
+    INVALID_DATE_LIKE_SUFFIX_REGEX
+        .get_or_init(|| INVALID_DATE_LIKE_SUFFIX_REGEX_STR.parse().unwrap())
+}
+
+/// Check a string for an invalid date-like suffix.
+#[must_use]
+pub fn has_invalid_date_like_suffix(facet: &str) -> bool {
+    debug_assert!(is_valid(facet));
+    invalid_date_like_suffix_regex().is_match(facet.as_bytes())
+}
+
+/// Parse the date-like suffix of a facet.
+///
+/// Returns `None` if the facet has no date-like suffix or if the
+/// date-like suffix does not encode a valid calendar date.
+#[must_use]
+pub fn date_suffix(facet: &str) -> Option<Date> {
+    if !has_date_like_suffix(facet) {
+        return None;
+    }
+    let (_, date) = try_split_into_prefix_and_parse_date_suffix(facet)?;
+    date
+}
+
+/// Outcome of parsing the date-like suffix of a facet
+///
+/// See also: [`explain_date_suffix()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSuffixReport<'a> {
+    /// The facet has no date-like suffix.
+    NoSuffix,
+
+    /// The date-like suffix encodes a valid calendar date.
+    ValidDate(Date),
+
+    /// The date-like suffix does not encode a valid calendar date.
+    InvalidDate {
+        /// The date-like suffix, including the leading `@`.
+        raw: &'a str,
+
+        /// The reason why parsing failed.
+        error: time::error::Parse,
+    },
+}
+
+/// Parse the date-like suffix of a facet and report the outcome.
+///
+/// In contrast to [`date_suffix()`] the reason why parsing the date
+/// failed is preserved.
+#[must_use]
+pub fn explain_date_suffix(facet: &str) -> DateSuffixReport<'_> {
+    if !has_date_like_suffix(facet) {
+        return DateSuffixReport::NoSuffix;
+    }
+    let Some((_, raw)) = try_split_into_prefix_and_date_like_suffix(facet) else {
+        return DateSuffixReport::NoSuffix;
+    };
+    match Date::parse(raw, DATE_SUFFIX_FORMAT) {
+        Ok(date) => DateSuffixReport::ValidDate(date),
+        Err(error) => DateSuffixReport::InvalidDate { raw, error },
+    }
+}
+
+/// Extract the raw year, month, and day from the date-like suffix of a facet.
+///
+/// In contrast to [`date_suffix()`] the components are not validated,
+/// i.e. they are also returned if they don't encode a valid calendar date.
+///
+/// Returns `None` if the facet has no date-like suffix.
+#[must_use]
+pub fn date_suffix_components(facet: &str) -> Option<(i32, u8, u8)> {
+    if !has_date_like_suffix(facet) {
+        return None;
+    }
+    let (_, suffix) = try_split_into_prefix_and_date_like_suffix(facet)?;
+    // Skip the leading '@'
+    let digits = &suffix[1..];
+    let year = digits[..4].parse().ok()?;
+    let month = digits[4..6].parse().ok()?;
+    let day = digits[6..].parse().ok()?;
+    Some((year, month, day))
+}
+
+/// Calculate the age of the [`date_suffix()`] of a facet relative to `today`.
+///
+/// The age is positive for dates in the past and negative for dates
+/// in the future.
+///
+/// Returns `None` if the facet has no valid date suffix.
+#[must_use]
+pub fn date_suffix_age(facet: &str, today: Date) -> Option<time::Duration> {
+    date_suffix(facet).map(|date| today - date)
+}
+
+/// Determine the ISO week of the [`date_suffix()`] of a facet.
+///
+/// Returns the ISO year and the ISO week, which might differ from the
+/// calendar year of the date around the turn of the year.
+///
+/// Returns `None` if the facet has no valid date suffix.
+#[must_use]
+pub fn date_suffix_iso_week(facet: &str) -> Option<(i32, u8)> {
+    let (iso_year, iso_week, _) = date_suffix(facet)?.to_iso_week_date();
+    Some((iso_year, iso_week))
+}
+
+/// The kind of a facet
+///
+/// See also: [`classify()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[allow(clippy::module_name_repetitions)]
+pub enum FacetKind {
+    /// An empty facet.
+    Empty,
+
+    /// Only a valid date suffix without a prefix.
+    DateOnly,
+
+    /// A prefix with a valid date suffix.
+    Dated {
+        /// The number of [`segments()`] of the prefix.
+        prefix_depth: usize,
+    },
+
+    /// A facet without a date-like suffix.
+    Plain {
+        /// The number of [`segments()`].
+        depth: usize,
+    },
+
+    /// A facet with a date-like suffix that does not encode a valid calendar date.
+    InvalidDate,
+}
+
+/// Classify a facet.
+///
+/// The date-like suffix is split off only once and the segments
+/// are counted on the remaining prefix.
+#[must_use]
+pub fn classify(facet: &str) -> FacetKind {
+    if facet.is_empty() {
+        return FacetKind::Empty;
+    }
+    let Some((prefix, date_like_suffix)) = split_off_date_like_suffix(facet) else {
+        return FacetKind::Plain {
+            depth: path_segments(facet, PATH_SEPARATOR).count(),
+        };
+    };
+    if Date::parse(date_like_suffix, DATE_SUFFIX_FORMAT).is_err() {
+        return FacetKind::InvalidDate;
+    }
+    match path_segments(prefix, PATH_SEPARATOR).count() {
+        0 => FacetKind::DateOnly,
+        prefix_depth => FacetKind::Dated { prefix_depth },
+    }
+}
+
+/// The parts of a facet
+///
+/// See also: [`decompose()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FacetParts<'a> {
+    /// The first segment, empty for date-only facets.
+    pub category: &'a str,
+
+    /// The remaining segments after the category, empty if absent.
+    pub subpath: &'a str,
+
+    /// The parsed date suffix, `None` if absent or invalid.
+    pub date: Option<Date>,
+
+    /// The date-like suffix, including the leading `@`.
+    pub raw_date_suffix: Option<&'a str>,
+}
+
+/// Decompose a facet into its category, sub-path, and date suffix.
+///
+/// For `genre/electronic/house@20220625` the category is `genre` and
+/// the sub-path is `electronic/house`.
+#[must_use]
+pub fn decompose(facet: &str) -> FacetParts<'_> {
+    let (path, raw_date_suffix) = split_off_date_like_suffix(facet)
+        .map_or((facet, None), |(prefix, suffix)| (prefix, Some(suffix)));
+    let (category, subpath) = path.split_once(PATH_SEPARATOR).unwrap_or((path, ""));
+    let date = raw_date_suffix.and_then(|suffix| Date::parse(suffix, DATE_SUFFIX_FORMAT).ok());
+    FacetParts {
+        category,
+        subpath,
+        date,
+        raw_date_suffix,
+    }
+}
+
+/// Describe the structure of a facet in a single line for diagnostic purposes.
+///
+/// Lists the [`segments()`] and the parsed date suffix, e.g.
+/// `segments=[genre, house] date=2022-06-25`. Invalid dates are reported
+/// with their raw suffix, e.g. `segments=[played] date=INVALID(@19700230)`.
+/// The date is omitted for facets without a date-like suffix.
+#[must_use]
+pub fn describe(facet: &str) -> String {
+    let segments = itertools::join(segments(facet), ", ");
+    match explain_date_suffix(facet) {
+        DateSuffixReport::NoSuffix => format!("segments=[{segments}]"),
+        DateSuffixReport::ValidDate(date) => format!("segments=[{segments}] date={date}"),
+        DateSuffixReport::InvalidDate { raw, .. } => {
+            format!("segments=[{segments}] date=INVALID({raw})")
+        }
+    }
+}
+
+/// Format the [`date_suffix()`] of a facet.
+///
+/// Returns `None` if the facet has no valid date suffix.
+#[must_use]
+pub fn date_suffix_formatted(
+    facet: &str,
+    format: &[FormatItem<'_>],
+) -> Option<Result<String, time::error::Format>> {
+    date_suffix(facet).map(|date| date.format(format))
+}
+
+/// Collect the dates of all facets with the given prefix.
+///
+/// Only facets with a valid [`date_suffix()`] and a prefix that
+/// equals `prefix` are considered. The dates are returned in
+/// ascending order without duplicates.
+#[must_use]
+pub fn collect_dates_for_prefix<F: Facet>(facets: &[F], prefix: &str) -> Vec<Date> {
+    let mut dates = facets
+        .iter()
+        .filter(|facet| facet.strip_date_like_suffix() == prefix)
+        .filter_map(FacetRef::date_suffix)
+        .collect::<Vec<_>>();
+    dates.sort_unstable();
+    dates.dedup();
+    dates
+}
+
+/// Find the earliest and the latest date of all facets with the given prefix.
+///
+/// See also: [`collect_dates_for_prefix()`]
+///
+/// Returns `None` if no facet with the given prefix has a valid date suffix.
+#[must_use]
+pub fn date_span_for_prefix<F: Facet>(facets: &[F], prefix: &str) -> Option<(Date, Date)> {
+    let dates = collect_dates_for_prefix(facets, prefix);
+    Some((*dates.first()?, *dates.last()?))
+}
+
+/// Intersect two lists of facets that are sorted by their bytes.
+///
+/// Both lists must be sorted in ascending order. The intersection is
+/// computed by merging both lists in linear time. Duplicates are matched
+/// pairwise, i.e. a facet occurs in the result as often as it occurs in
+/// the list with fewer occurrences. The references point into `lhs`.
+#[must_use]
+pub fn intersection_sorted<'a, F: Facet>(lhs: &'a [F], rhs: &'a [F]) -> Vec<&'a F> {
+    debug_assert!(lhs.windows(2).all(|w| w[0].as_ref() <= w[1].as_ref()));
+    debug_assert!(rhs.windows(2).all(|w| w[0].as_ref() <= w[1].as_ref()));
+    let mut intersection = Vec::new();
+    let mut lhs_iter = lhs.iter().peekable();
+    let mut rhs_iter = rhs.iter().peekable();
+    while let (Some(lhs_next), Some(rhs_next)) = (lhs_iter.peek(), rhs_iter.peek()) {
+        match lhs_next.as_ref().cmp(rhs_next.as_ref()) {
+            Ordering::Less => {
+                lhs_iter.next();
+            }
+            Ordering::Greater => {
+                rhs_iter.next();
+            }
+            Ordering::Equal => {
+                intersection.extend(lhs_iter.next());
+                rhs_iter.next();
+            }
+        }
+    }
+    intersection
+}
+
+/// Check if two collections contain the same facets, ignoring order and duplicates.
+///
+/// Facets are compared by their bytes.
+#[must_use]
+pub fn sets_equal<F: Facet>(lhs: &[F], rhs: &[F]) -> bool {
+    let lhs = lhs
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<std::collections::HashSet<&str>>();
+    let rhs = rhs
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<std::collections::HashSet<&str>>();
+    lhs == rhs
+}
+
+/// Collect the facets that are contained in only one of two collections.
+///
+/// See also: [`sets_equal()`]
+///
+/// Returns the facets from `lhs` that are missing in `rhs` followed by the
+/// facets from `rhs` that are missing in `lhs`. Each facet is returned only
+/// once, in the order of its first occurrence. The result is empty if
+/// both collections are [equal as sets](sets_equal()).
+#[must_use]
+pub fn symmetric_difference<'a, F: Facet>(lhs: &'a [F], rhs: &'a [F]) -> Vec<&'a F> {
+    let lhs_set = lhs
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<std::collections::HashSet<&str>>();
+    let rhs_set = rhs
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<std::collections::HashSet<&str>>();
+    let mut seen = std::collections::HashSet::new();
+    lhs.iter()
+        .filter(|facet| !rhs_set.contains(facet.as_ref()))
+        .chain(rhs.iter().filter(|facet| !lhs_set.contains(facet.as_ref())))
+        .filter(|&facet| seen.insert(facet.as_ref()))
+        .collect()
+}
+
+/// Find the dates in the range `start..=end` that are not covered by any
+/// facet with the given prefix.
+///
+/// See also: [`collect_dates_for_prefix()`]
+///
+/// The dates are returned in ascending order. No dates are returned if
+/// `start` is after `end`.
+#[must_use]
+pub fn missing_dates_for_prefix<F: Facet>(
+    facets: &[F],
+    prefix: &str,
+    start: Date,
+    end: Date,
+) -> Vec<Date> {
+    let dates = collect_dates_for_prefix(facets, prefix);
+    std::iter::successors(Some(start), |date| date.next_day())
+        .take_while(|date| *date <= end)
+        .filter(|date| dates.binary_search(date).is_err())
+        .collect()
+}
+
+/// Normalize a facet into Unicode Normalization Form C (NFC).
+///
+/// Returns the borrowed facet if it is already normalized.
+#[cfg(feature = "unicode-normalization")]
+#[must_use]
+pub fn normalize_nfc(facet: &str) -> Cow<'_, str> {
+    use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization as _};
+    if is_nfc_quick(facet.chars()) == IsNormalized::Yes {
+        return Cow::Borrowed(facet);
+    }
+    Cow::Owned(facet.nfc().collect())
+}
+
+/// Compare two facets after Unicode NFC normalization.
+///
+/// See also: [`normalize_nfc()`]
+#[cfg(feature = "unicode-normalization")]
+#[must_use]
+pub fn eq_nfc(lhs: &str, rhs: &str) -> bool {
+    lhs == rhs || normalize_nfc(lhs) == normalize_nfc(rhs)
+}
+
+/// Merge two facets with the same prefix by keeping the later date.
+///
+/// Returns `None` if the facets have different prefixes, i.e. if they
+/// differ after stripping their date-like suffixes.
+///
+/// Facets with a valid [`date_suffix()`] are preferred over facets
+/// without a valid date suffix. If both facets have the same date or
+/// if none of them has a valid date suffix then the first facet is
+/// returned.
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn merge_keep_later_date(lhs: &str, rhs: &str) -> Option<CompactFacet> {
+    if strip_date_like_suffix(lhs) != strip_date_like_suffix(rhs) {
+        return None;
+    }
+    let merged = match (date_suffix(lhs), date_suffix(rhs)) {
+        (Some(lhs_date), Some(rhs_date)) if rhs_date > lhs_date => rhs,
+        (None, Some(_)) => rhs,
+        _ => lhs,
+    };
+    Some(CompactFacet::from_str(merged))
+}
+
+/// Append a date suffix to a facet unless it already has a date-like suffix.
+///
+/// Facets that already have a date-like suffix are returned unmodified,
+/// which makes the operation idempotent.
+///
+/// # Errors
+///
+/// Returns a [`DatedFacetError`] if the undated facet is not a valid prefix
+/// or if the date could not be encoded.
+#[cfg(feature = "compact_str")]
+pub fn with_date_suffix_if_absent(
+    facet: &str,
+    date: Date,
+) -> Result<CompactFacet, DatedFacetError> {
+    if is_valid(facet) && has_date_like_suffix(facet) {
+        return Ok(CompactFacet::from_str(facet));
+    }
+    validate(facet).map_err(DatedFacetError::InvalidPrefix)?;
+    let facet = CompactFacet::from_prefix_with_date_suffix(facet, date)?;
+    Ok(facet)
+}
+
+/// Placeholder in templates for the date suffix of the current date.
+///
+/// See also: [`render_template_with_clock()`]
+#[cfg(feature = "compact_str")]
+pub const TEMPLATE_NOW_PLACEHOLDER: &str = "{@now}";
+
+/// Render a template by replacing [`TEMPLATE_NOW_PLACEHOLDER`] with the date
+/// suffix of the current date.
+///
+/// All occurrences of the placeholder are replaced, e.g. `chart{@now}` is
+/// rendered as `chart@20220625`. The resulting facet is validated.
+///
+/// # Errors
+///
+/// Returns a [`DatedFacetError`] if the current date could not be encoded
+/// or [`DatedFacetError::InvalidRendered`] if the rendered facet is invalid.
+#[cfg(feature = "compact_str")]
+pub fn render_template_with_clock<C: Clock>(
+    template: &str,
+    clock: &C,
+) -> Result<CompactFacet, DatedFacetError> {
+    let rendered = if template.contains(TEMPLATE_NOW_PLACEHOLDER) {
+        let date_suffix = format_date_suffix(clock.today())?;
+        template.replace(TEMPLATE_NOW_PLACEHOLDER, &date_suffix)
+    } else {
+        template.to_owned()
+    };
+    validate(&rendered).map_err(DatedFacetError::InvalidRendered)?;
+    Ok(CompactFacet::from_string(rendered))
+}
+
+/// Replace the date-like suffix of all facets with the date suffix of the
+/// current date.
+///
+/// Facets without a date-like suffix get one appended. Touching facets
+/// repeatedly on the same day results in the same facets.
+///
+/// # Errors
+///
+/// Returns a [`DatedFacetError`] if any facet is invalid or if the current
+/// date could not be encoded. The facets are not modified on error.
+#[cfg(feature = "compact_str")]
+pub fn touch_all<C: Clock>(
+    facets: &mut Vec<CompactFacet>,
+    clock: &C,
+) -> Result<(), DatedFacetError> {
+    let date_suffix = format_date_suffix(clock.today())?;
+    let touched = facets
+        .iter()
+        .map(|facet| {
+            validate(facet).map_err(DatedFacetError::InvalidPrefix)?;
+            let prefix = facet.strip_date_like_suffix();
+            Ok(CompactFacet::new(format_compact!("{prefix}{date_suffix}")))
+        })
+        .collect::<Result<Vec<_>, DatedFacetError>>()?;
+    *facets = touched;
+    Ok(())
+}
+
+/// Replace the day of the date suffix of a facet, keeping year and month.
+///
+/// Returns `Ok(None)` if the facet has no valid [`date_suffix()`].
+///
+/// # Errors
+///
+/// Returns a [`DatedFacetError`] if the resulting calendar date is invalid.
+#[cfg(feature = "compact_str")]
+pub fn set_date_suffix_day(facet: &str, day: u8) -> Result<Option<CompactFacet>, DatedFacetError> {
+    let Some(date) = date_suffix(facet) else {
+        return Ok(None);
+    };
+    let date = Date::from_calendar_date(date.year(), date.month(), day)
+        .map_err(DatedFacetError::InvalidDate)?;
+    let facet = CompactFacet::from_prefix_with_date_suffix(strip_date_like_suffix(facet), date)?;
+    Ok(Some(facet))
+}
+
+/// Move the date suffix of a facet to the front, e.g. for naming files
+/// chronologically.
+///
+/// The date digits are followed by `sep` and the prefix, e.g. `event@20220625`
+/// becomes `20220625-event` for `sep = '-'`. Facets with an empty prefix
+/// only result in the date digits, i.e. `@20220625` becomes `20220625`.
+///
+/// Returns `None` if the facet has no valid [`date_suffix()`].
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn date_suffix_to_prefix(facet: &str, sep: char) -> Option<CompactFacet> {
+    date_suffix(facet)?;
+    let (prefix, date_suffix) = try_split_into_prefix_and_date_like_suffix(facet)?;
+    // Skip the leading '@'
+    let digits = &date_suffix[1..];
+    let facet = if prefix.is_empty() {
+        CompactFacet::from_str(digits)
+    } else {
+        CompactFacet::from_string(format!("{digits}{sep}{prefix}"))
+    };
+    Some(facet)
+}
+
+/// Look up a value in a map with facet keys by a borrowed string.
+///
+/// Avoids to create a temporary facet for the lookup. Equivalent to
+/// `map.get::<str>(key)` because facets implement [`Borrow<str>`].
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn lookup<'a, V, S>(
+    map: &'a std::collections::HashMap<CompactFacet, V, S>,
+    key: &str,
+) -> Option<&'a V>
+where
+    S: std::hash::BuildHasher,
+{
+    map.get(key)
+}
+
+/// Try to repair a facet with a malformed date suffix.
+///
+/// The following near-miss date suffixes are detected:
+///
+/// - A trailing `@` after 8 digits, e.g. `played20220625@` is
+///   repaired as `played@20220625`.
+/// - 7 digits with a missing leading zero of the month, e.g.
+///   `played@2022625` is repaired as `played@20220625`.
+/// - 9 digits with a duplicated digit in the month or day,
+///   e.g. `played@202206625` is repaired as `played@20220625`.
+///
+/// Only repairs that result in a valid [`date_suffix()`] are considered.
+///
+/// Returns `None` if the facet is invalid, if it already has a valid date
+/// suffix, if none of the patterns matches, or if the repair is ambiguous.
+/// Valid ordinal date suffixes with 7 digits are never repaired, see also
+/// [`try_split_into_prefix_and_ordinal_date()`]. This excludes all months
+/// before April and also all dates with a missing leading zero of the day.
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn try_repair_date_suffix(facet: &str) -> Option<CompactFacet> {
+    if !is_valid(facet) || date_suffix(facet).is_some() {
+        return None;
+    }
+    let mut candidates = if let Some(undelimited) = facet.strip_suffix('@') {
+        let (prefix, digits) = try_split_off_suffix_len(undelimited, DATE_LIKE_SUFFIX_LEN - 1)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        vec![format!("{prefix}@{digits}")]
+    } else {
+        let (prefix, digits) = facet.rsplit_once('@')?;
+        if digits.len() < 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let (year, month_day) = digits.split_at(4);
+        let repaired_digits = match digits.len() {
+            7 => {
+                if try_split_into_prefix_and_ordinal_date(facet).is_some() {
+                    return None;
+                }
+                vec![format!("{year}0{month_day}")]
+            }
+            9 => (1..month_day.len())
+                .filter(|&i| month_day.as_bytes()[i - 1] == month_day.as_bytes()[i])
+                .map(|i| format!("{year}{}{}", &month_day[..i], &month_day[i + 1..]))
+                .collect(),
+            _ => return None,
+        };
+        repaired_digits
+            .into_iter()
+            .map(|digits| format!("{prefix}@{digits}"))
+            .collect()
+    };
+    candidates.retain(|candidate| date_suffix(candidate).is_some());
+    candidates.sort_unstable();
+    candidates.dedup();
+    let [repaired] = <[String; 1]>::try_from(candidates).ok()?;
+    Some(CompactFacet::from_string(repaired))
+}
+
+/// Separator between the segments of hierarchical facets.
+pub const PATH_SEPARATOR: char = '/';
+
+/// Strip the date-like suffix from a facet.
+///
+/// Returns the facet unmodified if it has no date-like suffix.
+#[must_use]
+pub fn strip_date_like_suffix(facet: &str) -> &str {
+    debug_assert!(is_valid(facet));
+    if !has_date_like_suffix(facet) {
+        return facet;
+    }
+    try_split_into_prefix_and_date_like_suffix(facet).map_or(facet, |(prefix, _)| prefix)
+}
+
+/// Find the start indices of all date-like chunks, i.e. `@` followed
+/// by exactly 8 digits, within a facet.
+fn date_like_chunk_indices(facet: &str) -> impl Iterator<Item = usize> + '_ {
+    let bytes = facet.as_bytes();
+    facet
+        .match_indices('@')
+        .map(|(index, _)| index)
+        .filter(move |&index| {
+            let digits = &bytes[index + 1..];
+            digits.len() >= 8
+                && digits[..8].iter().all(u8::is_ascii_digit)
+                && !digits.get(8).is_some_and(u8::is_ascii_digit)
+        })
+}
+
+/// Check if a facet contains more than one date-like chunk.
+///
+/// In contrast to [`has_date_like_suffix()`] all occurrences of `@`
+/// followed by exactly 8 digits are considered, not only the suffix,
+/// e.g. `event@20220101@20220202`.
+#[must_use]
+pub fn has_multiple_date_like_suffixes(facet: &str) -> bool {
+    date_like_chunk_indices(facet).nth(1).is_some()
+}
+
+/// Strip everything starting at the first date-like chunk of a facet.
+///
+/// See also: [`has_multiple_date_like_suffixes()`]
+///
+/// Returns the facet unmodified if it doesn't contain any date-like chunks.
+#[must_use]
+pub fn strip_all_date_like_suffixes(facet: &str) -> &str {
+    date_like_chunk_indices(facet)
+        .next()
+        .map_or(facet, |index| &facet[..index])
+}
+
+/// Split a facet into its hierarchical path segments.
+///
+/// The segments are separated by [`PATH_SEPARATOR`]. A date-like suffix
+/// is stripped before splitting and never becomes part of the last
+/// segment. Facets that are either empty or only consist of a date-like
+/// suffix don't have any segments.
+///
+/// The first segment of [absolute](is_absolute) facets is the empty root
+/// segment, followed by the segments of the remaining relative facet.
+pub fn segments(facet: &str) -> impl Iterator<Item = &str> {
+    segments_with(facet, PATH_SEPARATOR)
+}
+
+/// Get the path segment of a facet at the given index.
+///
+/// See also: [`segments()`]
+///
+/// Returns `None` if the index is out of range.
+#[must_use]
+pub fn segment(facet: &str, index: usize) -> Option<&str> {
+    segments(facet).nth(index)
+}
+
+/// Split off a trailing version segment from a facet.
+///
+/// A version segment consists of a `v` followed by decimal digits, e.g.
+/// `playlist/summer/v3`. The date-like suffix is stripped first and is
+/// not part of the returned base, i.e. for `playlist/summer/v3@20220625`
+/// the base is `playlist/summer` with version 3.
+///
+/// Returns the facet with the date-like suffix stripped without any version
+/// if the last segment is not a version segment.
+#[must_use]
+pub fn try_split_off_version_segment(facet: &str) -> (&str, Option<u32>) {
+    let path = strip_date_like_suffix(facet);
+    let (base, last_segment) = path.rsplit_once(PATH_SEPARATOR).unwrap_or(("", path));
+    let version = last_segment
+        .strip_prefix('v')
+        .filter(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|digits| digits.parse().ok());
+    match version {
+        Some(version) => (base, Some(version)),
+        None => (path, None),
+    }
+}
+
+/// Split a facet into its hierarchical path segments, using a custom separator.
+///
+/// See also: [`segments()`]
+///
+/// Absolute facets are always marked by a leading [`PATH_SEPARATOR`],
+/// independent of `sep`.
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+pub fn segments_with(facet: &str, sep: char) -> impl Iterator<Item = &str> {
+    debug_assert!(is_valid_allowing_leading_slash(facet));
+    assert_custom_separator(sep);
+    let relative = facet.strip_prefix(PATH_SEPARATOR).unwrap_or(facet);
+    let path = &facet[..facet.len() - relative.len() + strip_date_like_suffix(relative).len()];
+    path_segments(path, sep)
+}
+
+/// Split a path without a date-like suffix into its segments.
+fn path_segments(path: &str, sep: char) -> impl Iterator<Item = &str> {
+    let (root, relative) = match path.strip_prefix(PATH_SEPARATOR) {
+        Some(relative) => (Some(""), relative),
+        None => (None, path),
+    };
+    root.into_iter().chain(
+        (!relative.is_empty())
+            .then(|| relative.split(sep))
+            .into_iter()
+            .flatten(),
+    )
+}
+
+/// Reject separators that would conflict with date-like suffixes.
+fn assert_custom_separator(sep: char) {
+    assert_ne!(
+        '@', sep,
+        "the separator of date-like suffixes cannot separate segments"
+    );
+}
+
+/// Split a facet into the byte ranges of its path segments and date-like suffix.
+///
+/// Yields the [`segments()`] together with their byte ranges in the facet,
+/// followed by the date-like suffix (if present) as a separate span. The
+/// ranges tile the whole facet with gaps only at the separators between
+/// path segments.
+pub fn segment_spans(facet: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    segment_spans_with(facet, PATH_SEPARATOR)
+}
+
+/// Split a facet into the byte ranges of its path segments and date-like suffix,
+/// using a custom separator.
+///
+/// See also: [`segment_spans()`], [`segments_with()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+pub fn segment_spans_with(facet: &str, sep: char) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let relative = facet.strip_prefix(PATH_SEPARATOR).unwrap_or(facet);
+    let root_len = facet.len() - relative.len();
+    let path_len = root_len + strip_date_like_suffix(relative).len();
+    let mut offset = 0;
+    // The empty root segment is always followed by a `PATH_SEPARATOR`
+    let mut separator_len = if root_len > 0 {
+        root_len
+    } else {
+        sep.len_utf8()
+    };
+    let segment_spans = segments_with(facet, sep).map(move |segment| {
+        let range = offset..offset + segment.len();
+        offset = range.end + separator_len;
+        separator_len = sep.len_utf8();
+        (range, segment)
+    });
+    let date_like_suffix_span =
+        (path_len < facet.len()).then(|| (path_len..facet.len(), &facet[path_len..]));
+    segment_spans.chain(date_like_suffix_span)
+}
+
+/// Iterate over all strict ancestors of a facet.
+///
+/// The ancestors are yielded from the shallowest to the deepest, i.e.
+/// `genre` and `genre/electronic` for `genre/electronic/house`. The facet
+/// itself and the empty root of [absolute](is_absolute) facets are excluded.
+/// A date-like suffix is stripped before and is not part of any ancestor.
+pub fn ancestors(facet: &str) -> impl Iterator<Item = &str> {
+    ancestors_with(facet, PATH_SEPARATOR)
+}
+
+/// Iterate over all strict ancestors of a facet, using a custom separator.
+///
+/// See also: [`ancestors()`], [`segments_with()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+pub fn ancestors_with(facet: &str, sep: char) -> impl Iterator<Item = &str> {
+    assert_custom_separator(sep);
+    let relative = facet.strip_prefix(PATH_SEPARATOR).unwrap_or(facet);
+    let path = &facet[..facet.len() - relative.len() + strip_date_like_suffix(relative).len()];
+    path.match_indices(sep)
+        .map(move |(index, _)| &path[..index])
+        .filter(|ancestor| !ancestor.is_empty())
+}
+
+/// Find the deepest strict [ancestor](ancestors()) of a facet in a set of facets.
+///
+/// The facet itself is not considered. Ancestors are compared
+/// literally with the facets in the set.
+#[must_use]
+pub fn nearest_ancestor_in<'a, F: Facet>(facet: &str, set: &'a [F]) -> Option<&'a F> {
+    let ancestors = ancestors(facet).collect::<Vec<_>>();
+    ancestors
+        .into_iter()
+        .rev()
+        .find_map(|ancestor| set.iter().find(|facet| facet.as_ref() == ancestor))
+}
+
+/// Count the occurrences of [`PATH_SEPARATOR`] in a facet.
+///
+/// For facets with at least one segment the number of [`segments()`]
+/// exceeds the number of separators by one, including empty segments
+/// between doubled separators, e.g. `a//b` has 2 separators and 3 segments.
+#[must_use]
+pub fn separator_count(facet: &str) -> usize {
+    separator_count_with(facet, PATH_SEPARATOR)
+}
+
+/// Count the occurrences of a custom separator in a facet.
+///
+/// See also: [`separator_count()`], [`segments_with()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+#[must_use]
+pub fn separator_count_with(facet: &str, sep: char) -> usize {
+    assert_custom_separator(sep);
+    facet.matches(sep).count()
+}
+
+/// Check if a facet starts with the given path prefix.
+///
+/// The comparison is segment-aware, i.e. the prefix `genre` matches both
+/// `genre` and `genre/house` but not `genres`. The empty prefix matches
+/// all facets. Date-like suffixes of both the facet and the prefix are
+/// ignored, see [`segments()`].
+#[must_use]
+pub fn starts_with_path_prefix(facet: &str, prefix: &str) -> bool {
+    starts_with_path_prefix_with(facet, prefix, PATH_SEPARATOR)
+}
+
+/// Check if a facet starts with the given path prefix, using a custom separator.
+///
+/// See also: [`starts_with_path_prefix()`], [`segments_with()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+#[must_use]
+pub fn starts_with_path_prefix_with(facet: &str, prefix: &str, sep: char) -> bool {
+    let mut facet_segments = segments_with(facet, sep);
+    segments_with(prefix, sep).all(|prefix_segment| facet_segments.next() == Some(prefix_segment))
+}
+
+/// Check that all facets start with the given path prefix.
+///
+/// See also: [`starts_with_path_prefix()`]
+///
+/// # Errors
+///
+/// Returns all facets that don't start with the prefix in their original order.
+pub fn all_under_prefix<'a, F: Facet>(facets: &'a [F], prefix: &str) -> Result<(), Vec<&'a F>> {
+    let violations = facets
+        .iter()
+        .filter(|facet| !facet.starts_with_path_prefix(prefix))
+        .collect::<Vec<_>>();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Strip a path prefix from a facet and return the remainder.
+///
+/// The prefix must match on a segment boundary, i.e. `genre` is stripped
+/// from `genre/house` resulting in `house`, but not from `genres/house`.
+/// The remainder of a facet that equals the prefix is either empty or
+/// only consists of the date-like suffix of the facet. The empty prefix
+/// matches all facets. Like in [`starts_with_path_prefix()`] a date-like
+/// suffix of the prefix is ignored.
+///
+/// Returns `None` if the facet doesn't start with the prefix.
+#[must_use]
+pub fn strip_path_prefix<'a>(facet: &'a str, prefix: &str) -> Option<&'a str> {
+    strip_path_prefix_with(facet, prefix, PATH_SEPARATOR)
+}
+
+/// Strip a path prefix from a facet and return the remainder, using a custom separator.
+///
+/// See also: [`strip_path_prefix()`], [`segments_with()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+#[must_use]
+pub fn strip_path_prefix_with<'a>(facet: &'a str, prefix: &str, sep: char) -> Option<&'a str> {
+    let mut facet_segments = segments_with(facet, sep);
+    let matched_count = segments_with(prefix, sep).try_fold(0, |count, prefix_segment| {
+        (facet_segments.next()? == prefix_segment).then_some(count + 1)
+    })?;
+    // The next span is either the first remaining segment or the date-like suffix
+    let remainder_start = segment_spans_with(facet, sep)
+        .nth(matched_count)
+        .map_or(facet.len(), |(range, _)| range.start);
+    Some(&facet[remainder_start..])
+}
+
+/// Join a relative facet onto a base facet, separated by a single [`PATH_SEPARATOR`].
+///
+/// If either `base` or `rel` is empty the other one is returned. If `rel`
+/// only consists of a date-like suffix it is appended to `base` without
+/// a separator, e.g. `genre` and `@20220625` are joined as `genre@20220625`.
+///
+/// # Errors
+///
+/// Returns [`JoinError::InvalidFacet`] if either `base` or `rel` is invalid,
+/// e.g. if `rel` starts with a [`PATH_SEPARATOR`] or contains leading or
+/// trailing whitespace. Returns [`JoinError::DatedBase`] if `base` has a
+/// date-like suffix.
+#[cfg(feature = "compact_str")]
+pub fn join(base: &str, rel: &str) -> Result<CompactFacet, JoinError> {
+    validate(base)?;
+    validate(rel)?;
+    if has_date_like_suffix(base) {
+        return Err(JoinError::DatedBase);
+    }
+    if base.is_empty() {
+        return Ok(CompactFacet::from_str(rel));
+    }
+    if rel.is_empty() {
+        return Ok(CompactFacet::from_str(base));
+    }
+    if strip_date_like_suffix(rel).is_empty() {
+        return Ok(CompactFacet::new(format_compact!("{base}{rel}")));
+    }
+    let joined = format_compact!("{base}{PATH_SEPARATOR}{rel}");
+    Ok(CompactFacet::new(joined))
+}
+
+/// Compare two facets case-insensitively, except for their date-like suffixes.
+///
+/// The prefixes are compared by ignoring ASCII case, while the date-like
+/// suffixes must be identical.
+#[must_use]
+pub fn eq_case_insensitive_path_exact_date(lhs: &str, rhs: &str) -> bool {
+    let lhs_path = strip_date_like_suffix(lhs);
+    let rhs_path = strip_date_like_suffix(rhs);
+    lhs_path.eq_ignore_ascii_case(rhs_path) && lhs[lhs_path.len()..] == rhs[rhs_path.len()..]
+}
+
+/// Remove case-insensitive duplicates, keeping the first occurrence.
+///
+/// Facets are compared by their lowercase representation. The first
+/// occurrence of each facet is retained verbatim and the order of the
+/// remaining facets is preserved.
+#[must_use]
+pub fn dedup_case_insensitive_keep_first<F: Facet>(facets: impl IntoIterator<Item = F>) -> Vec<F> {
+    let mut seen = std::collections::HashSet::new();
+    facets
+        .into_iter()
+        .filter(|facet| seen.insert(facet.as_ref().to_lowercase()))
+        .collect()
+}
+
+/// Remove facets with duplicate topics, keeping the latest date.
+///
+/// Facets are grouped by their topic, i.e. with any date-like suffix
+/// stripped. Each group is reduced to a single facet by [`merge_keep_later_date()`],
+/// i.e. facets with a valid date suffix are preferred and the first
+/// occurrence is kept for equal dates. The groups are ordered by the
+/// first occurrence of their topic.
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn dedup_by_topic_keep_latest<F: Facet>(
+    facets: impl IntoIterator<Item = F>,
+) -> Vec<CompactFacet> {
+    let mut deduped = Vec::<CompactFacet>::new();
+    let mut topic_indices = std::collections::HashMap::<String, usize>::new();
+    for facet in facets {
+        let facet = facet.as_ref();
+        let topic = strip_date_like_suffix(facet);
+        if let Some(&index) = topic_indices.get(topic) {
+            if let Some(merged) = merge_keep_later_date(&deduped[index], facet) {
+                deduped[index] = merged;
+            }
+        } else {
+            topic_indices.insert(topic.to_owned(), deduped.len());
+            deduped.push(CompactFacet::from_str(facet));
+        }
+    }
+    deduped
+}
+
+/// Find facets without any relatives in their parent path.
+///
+/// A facet is an orphan if its topic, i.e. the facet with any date-like
+/// suffix stripped, has at least two [`segments()`] and no other facet
+/// with a different topic [starts with](starts_with_path_prefix()) the
+/// parent path of the topic. The other facet could either be the parent
+/// itself, a sibling, or a descendant of a sibling. Facets that only differ
+/// by their date-like suffix are not related. Top-level facets are never
+/// orphans.
+///
+/// Orphans may indicate typos in the path. The orphans are returned in
+/// their original order. The runtime is quadratic in the number of facets.
+#[must_use]
+pub fn find_orphans<F: Facet>(facets: &[F]) -> Vec<&F> {
+    facets
+        .iter()
+        .filter(|facet| {
+            let topic = facet.strip_date_like_suffix();
+            let Some((parent, _)) = topic.rsplit_once(PATH_SEPARATOR) else {
+                return false;
+            };
+            !facets.iter().any(|other| {
+                other.strip_date_like_suffix() != topic && other.starts_with_path_prefix(parent)
+            })
+        })
+        .collect()
+}
+
+/// Check if a facet is strictly more specific than another facet.
+///
+/// The precedence is:
+///
+/// 1. The facet with more [`segments()`] is more specific.
+/// 2. If both facets have the same number of segments, then a facet with
+///    a valid [`date_suffix()`] is more specific than a facet without.
+///
+/// Otherwise neither facet is more specific than the other.
+#[must_use]
+pub fn is_more_specific_than(lhs: &str, rhs: &str) -> bool {
+    match segments(lhs).count().cmp(&segments(rhs).count()) {
+        Ordering::Less => false,
+        Ordering::Greater => true,
+        Ordering::Equal => date_suffix(lhs).is_some() && date_suffix(rhs).is_none(),
+    }
+}
+
+/// Compare two facets by their UTF-8 bytes.
+///
+/// This is the canonical total order of facets that is implemented by
+/// [`Ord`] for all facet types of this crate. Facets are ordered
+/// lexicographically byte by byte, with a prefix ordered before all
+/// longer facets that start with it. The order is consistent with [`Eq`],
+/// i.e. two facets compare as equal if and only if their bytes are equal.
+///
+/// See also: [`cmp_hierarchical()`]
+#[must_use]
+pub fn total_cmp(lhs: &str, rhs: &str) -> Ordering {
+    lhs.as_bytes().cmp(rhs.as_bytes())
+}
+
+/// Compare two facets hierarchically, i.e. segment by segment.
+///
+/// In contrast to the lexical ordering a facet is ordered before all of
+/// its descendants and siblings are ordered lexically within each level,
+/// e.g. `genre/house` < `genre/house/deep` < `genre/house-music`.
+///
+/// The date-like suffix is considered as part of the final segment, i.e.
+/// `genre/house` < `genre/house/deep` < `genre/house@20220625`.
+#[must_use]
+pub fn cmp_hierarchical(lhs: &str, rhs: &str) -> Ordering {
+    cmp_hierarchical_with(lhs, rhs, PATH_SEPARATOR)
+}
+
+/// Compare two facets hierarchically, using a custom separator.
+///
+/// See also: [`cmp_hierarchical()`]
+///
+/// # Panics
+///
+/// Panics if `sep` is the `@` of date-like suffixes.
+#[must_use]
+pub fn cmp_hierarchical_with(lhs: &str, rhs: &str, sep: char) -> Ordering {
+    assert_custom_separator(sep);
+    lhs.split(sep).cmp(rhs.split(sep))
+}
+
+/// Compare two facets by their [`date_suffix()`] in descending order.
+///
+/// Facets without a valid date suffix are ordered after all facets with a
+/// valid date suffix. Ties are broken by comparing the facets without their
+/// date-like suffixes and finally the whole facets lexically.
+#[must_use]
+pub fn cmp_by_date_desc(lhs: &str, rhs: &str) -> Ordering {
+    let lhs_date = date_suffix(lhs);
+    let rhs_date = date_suffix(rhs);
+    match (lhs_date, rhs_date) {
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        _ => rhs_date.cmp(&lhs_date),
+    }
+    .then_with(|| strip_date_like_suffix(lhs).cmp(strip_date_like_suffix(rhs)))
+    .then_with(|| lhs.cmp(rhs))
+}
+
+/// Check if a facet matches a glob pattern of path segments.
+///
+/// The pattern is split into segments like a facet. A `*` segment matches
+/// exactly one arbitrary segment and a `**` segment matches any number of
+/// segments, including none. All other segments must match literally.
+///
+/// Only the [`segments()`] of the facet are matched, i.e. a date-like
+/// suffix of the facet is ignored.
+#[must_use]
+pub fn matches_glob(facet: &str, pattern: &str) -> bool {
+    fn matches(segments: &[&str], pattern: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => segments.is_empty(),
+            Some((&"**", pattern_rest)) => {
+                (0..=segments.len()).any(|skip| matches(&segments[skip..], pattern_rest))
+            }
+            Some((&pattern_first, pattern_rest)) => match segments.split_first() {
+                Some((&first, rest)) => {
+                    (pattern_first == "*" || pattern_first == first) && matches(rest, pattern_rest)
+                }
+                None => false,
+            },
+        }
+    }
+    let segments = segments(facet).collect::<Vec<_>>();
+    let pattern = pattern.split(PATH_SEPARATOR).collect::<Vec<_>>();
+    matches(&segments, &pattern)
+}
+
+/// Select all facets that [match](matches_glob) the given glob pattern.
+///
+/// The order of the selected facets is preserved.
+#[must_use]
+pub fn expand_pattern<'a, F: Facet>(pattern: &str, universe: &'a [F]) -> Vec<&'a F> {
+    universe
+        .iter()
+        .filter(|facet| matches_glob(facet.as_ref(), pattern))
+        .collect()
+}
+
+/// Check that a facet only contains allowed characters.
+///
+/// A date-like suffix is stripped before checking the characters, i.e.
+/// the `@` and the decimal digits of the suffix don't need to be
+/// contained in the set of allowed characters.
+#[must_use]
+pub fn is_valid_charset(facet: &str, allowed: &CharSet) -> bool {
+    allowed.contains_all(strip_date_like_suffix(facet))
+}
+
+/// Check if a facet belongs to one of the reserved namespaces.
+///
+/// The facet is reserved if it [starts with](starts_with_path_prefix)
+/// any of the given path prefixes.
+#[must_use]
+pub fn is_reserved(facet: &str, reserved_prefixes: &[&str]) -> bool {
+    reserved_prefixes
+        .iter()
+        .any(|prefix| starts_with_path_prefix(facet, prefix))
+}
+
+/// The apparent hierarchy separator of a facet
+///
+/// Only [`SeparatorStyle::Slash`] is canonical, see [`PATH_SEPARATOR`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorStyle {
+    /// No separator
+    None,
+
+    /// Only slashes `/`
+    Slash,
+
+    /// Only backslashes `\`
+    Backslash,
+
+    /// Only dots `.`
+    Dot,
+
+    /// Different separators
+    Mixed,
+}
+
+/// Classify the apparent hierarchy separator of a facet.
+///
+/// Only the slash `/`, the backslash `\`, and the dot `.` are considered
+/// as separators. A date-like suffix is ignored.
+#[must_use]
+pub fn detect_separator_style(facet: &str) -> SeparatorStyle {
+    let mut style = SeparatorStyle::None;
+    for byte in strip_date_like_suffix(facet).bytes() {
+        let next_style = match byte {
+            b'/' => SeparatorStyle::Slash,
+            b'\\' => SeparatorStyle::Backslash,
+            b'.' => SeparatorStyle::Dot,
+            _ => continue,
+        };
+        if style == SeparatorStyle::None {
+            style = next_style;
+        } else if style != next_style {
+            return SeparatorStyle::Mixed;
+        }
+    }
+    style
+}
+
+/// Render the path of a facet with a custom separator.
+///
+/// Replaces each [`PATH_SEPARATOR`] in the facet by `separator`. A date-like
+/// suffix is preserved verbatim and is appended to the last segment.
+///
+/// Returns the borrowed facet if nothing needs to be replaced.
+#[must_use]
+pub fn render_path<'a>(facet: &'a str, separator: &str) -> Cow<'a, str> {
+    let path = strip_date_like_suffix(facet);
+    if separator == PATH_SEPARATOR.encode_utf8(&mut [0; 4]) || !path.contains(PATH_SEPARATOR) {
+        return Cow::Borrowed(facet);
+    }
+    let mut rendered = path.replace(PATH_SEPARATOR, separator);
+    rendered.push_str(&facet[path.len()..]);
+    Cow::Owned(rendered)
+}
+
+/// Quote a facet for a CSV field according to RFC 4180.
+///
+/// Only facets that contain commas, double quotes, or line breaks are
+/// quoted. Double quotes within the facet are escaped by doubling them.
+#[must_use]
+pub fn to_csv_field(facet: &str) -> Cow<'_, str> {
+    if !facet.contains([',', '"', '\r', '\n']) {
+        return Cow::Borrowed(facet);
+    }
+    Cow::Owned(format!("\"{}\"", facet.replace('"', "\"\"")))
+}
+
+/// Unquote and validate a facet from a CSV field according to RFC 4180.
+///
+/// The inverse of [`to_csv_field()`].
+///
+/// # Errors
+///
+/// Returns a [`FacetFromCsvError`] if the field is not quoted properly
+/// or if the unquoted field is not a valid facet.
+pub fn from_csv_field(field: &str) -> Result<Cow<'_, str>, FacetFromCsvError> {
+    let facet = if let Some(quoted) = field.strip_prefix('"') {
+        let quoted = quoted
+            .strip_suffix('"')
+            .ok_or(FacetFromCsvError::InvalidQuoting)?;
+        if quoted.replace("\"\"", "").contains('"') {
+            return Err(FacetFromCsvError::InvalidQuoting);
+        }
+        if quoted.contains('"') {
+            Cow::Owned(quoted.replace("\"\"", "\""))
+        } else {
+            Cow::Borrowed(quoted)
+        }
+    } else {
+        if field.contains([',', '"', '\r', '\n']) {
+            return Err(FacetFromCsvError::InvalidQuoting);
+        }
+        Cow::Borrowed(field)
+    };
+    validate(&facet)?;
+    Ok(facet)
+}
+
+/// Characters that are not permitted in file names on all platforms.
+const FILENAME_RESERVED_CHARS: &[char] = &['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
+
+/// Device names that are reserved on Windows, even with an extension.
+const FILENAME_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Convert a facet into a file name that is safe on all platforms.
+///
+/// Path separators, characters that are reserved on Windows, and control
+/// characters are replaced by `_`. Trailing dots and spaces are trimmed.
+/// Reserved device names like `CON` or `nul.txt` are prefixed with `_`.
+/// Empty results are replaced by `_`.
+///
+/// The transformation is deterministic, but lossy and cannot be reversed.
+#[must_use]
+pub fn sanitize_for_filename(facet: &str) -> String {
+    let mut filename = facet
+        .chars()
+        .map(|c| {
+            if c.is_control() || FILENAME_RESERVED_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    filename.truncate(filename.trim_end_matches(['.', ' ']).len());
+    let stem = filename.split('.').next().unwrap_or_default();
+    if FILENAME_RESERVED_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(stem.trim_end()))
+    {
+        filename.insert(0, '_');
+    }
+    if filename.is_empty() {
+        filename.push('_');
+    }
+    filename
+}
+
+/// Convert a facet into a slug, e.g. for use in URLs.
+///
+/// The [`PATH_SEPARATOR`] is replaced by `-` and ASCII characters are
+/// converted to lowercase. The `@` of a date-like suffix is replaced by
+/// `-`, i.e. the date digits are appended as a trailing `-yyyyMMdd`.
+/// All other characters are preserved.
+///
+/// The transformation is lossy and cannot be reversed.
+#[must_use]
+pub fn to_slug(facet: &str) -> String {
+    debug_assert!(is_valid(facet));
+    let path = strip_date_like_suffix(facet);
+    let mut slug = path.replace(PATH_SEPARATOR, "-");
+    slug.make_ascii_lowercase();
+    if path.len() < facet.len() {
+        // Date-like suffix
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&facet[path.len() + 1..]);
+    }
+    slug
+}
+
+/// Convert the first character of each word to ASCII uppercase.
+fn push_title_case_ascii(out: &mut String, text: &str) {
+    let mut word_start = true;
+    for ch in text.chars() {
+        out.push(if word_start {
+            ch.to_ascii_uppercase()
+        } else {
+            ch
+        });
+        word_start = ch.is_whitespace();
+    }
+}
+
+/// Create a human-readable label for displaying a facet.
+///
+/// The first character of each word in the last of the [`segments()`]
+/// is converted to ASCII uppercase. A valid date suffix is appended
+/// in parentheses, e.g. `genre/deep house@20220625` is displayed as
+/// `Deep House (2022-06-25)`.
+#[must_use]
+pub fn display_label(facet: &str) -> String {
+    let leaf = segments(facet).last().unwrap_or_default();
+    let mut label = String::with_capacity(leaf.len());
+    push_title_case_ascii(&mut label, leaf);
+    if let Some(date) = date_suffix(facet) {
+        if label.is_empty() {
+            label = date.to_string();
+        } else {
+            label = format!("{label} ({date})");
+        }
+    }
+    label
+}
+
+/// Encode a facet into its binary representation and append it to `out`.
+///
+/// The binary representation consists of the length in bytes as an
+/// unsigned LEB128 varint followed by the UTF-8 bytes of the facet.
+/// Facets can be concatenated and decoded one after another.
+pub fn encode_binary(facet: &str, out: &mut Vec<u8>) {
+    let mut len = facet.len();
+    loop {
+        let [low_byte, ..] = len.to_le_bytes();
+        let byte = low_byte & 0x7f;
+        len >>= 7;
+        if len == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+    out.extend_from_slice(facet.as_bytes());
+}
+
+/// Decode a facet from the start of its binary representation.
+///
+/// See also: [`encode_binary()`]
+///
+/// Returns the decoded facet and the remaining input.
+///
+/// # Errors
+///
+/// Returns a [`BinaryDecodeError`] if the input is truncated or malformed
+/// or if the decoded facet is invalid.
+#[cfg(feature = "compact_str")]
+pub fn decode_binary(input: &[u8]) -> Result<(CompactFacet, &[u8]), BinaryDecodeError> {
+    let mut len = 0usize;
+    let mut shift = 0;
+    let mut remainder = input;
+    loop {
+        let (&byte, next_remainder) = remainder
+            .split_first()
+            .ok_or(BinaryDecodeError::Truncated)?;
+        remainder = next_remainder;
+        let bits = usize::from(byte & 0x7f);
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return Err(BinaryDecodeError::LengthOverflow);
+        }
+        len |= bits << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    if remainder.len() < len {
+        return Err(BinaryDecodeError::Truncated);
+    }
+    let (bytes, remainder) = remainder.split_at(len);
+    let facet = CompactFacet::try_from(bytes).map_err(|err| match err {
+        FacetFromBytesError::InvalidUtf8(err) => BinaryDecodeError::InvalidUtf8(err),
+        FacetFromBytesError::InvalidFacet(err) => BinaryDecodeError::InvalidFacet(err),
+    })?;
+    Ok((facet, remainder))
+}
+
+/// Create breadcrumbs for displaying the path of a facet.
+///
+/// The [`segments()`] are title-cased like for [`display_label()`] and
+/// joined by `separator`, e.g. `genre/electronic/house` is displayed as
+/// `Genre › Electronic › House` with the separator ` › `. A valid date
+/// suffix is either omitted or appended in parentheses, depending on
+/// `with_date`.
+#[must_use]
+pub fn breadcrumbs(facet: &str, separator: &str, with_date: bool) -> String {
+    let mut breadcrumbs = String::with_capacity(facet.len());
+    for (index, segment) in segments(facet).enumerate() {
+        if index > 0 {
+            breadcrumbs.push_str(separator);
+        }
+        push_title_case_ascii(&mut breadcrumbs, segment);
+    }
+    if with_date {
+        if let Some(date) = date_suffix(facet) {
+            if breadcrumbs.is_empty() {
+                breadcrumbs = date.to_string();
+            } else {
+                breadcrumbs = format!("{breadcrumbs} ({date})");
+            }
+        }
+    }
+    breadcrumbs
+}
+
+/// Stable 32-bit FNV-1a hash, independent of the platform and the Rust version.
+fn stable_hash(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Anonymize a facet, e.g. for logging.
+///
+/// The structure of the facet is preserved while the contents of each
+/// segment are replaced by a short, stable token, e.g. `seg_3f9a1c/seg_1a2b3c@20220625`.
+/// Any date-like suffix is kept intact.
+///
+/// Identical segments are always mapped onto the same token. The
+/// transformation is not reversible.
+#[must_use]
+pub fn to_anonymized(facet: &str) -> String {
+    debug_assert!(is_valid(facet));
+    let path = strip_date_like_suffix(facet);
+    let mut anonymized = String::with_capacity(facet.len());
+    if !path.is_empty() {
+        let tokens = path
+            .split(PATH_SEPARATOR)
+            .map(|segment| format!("seg_{:06x}", stable_hash(segment.as_bytes()) >> 8));
+        anonymized.push_str(&itertools::join(tokens, "/"));
+    }
+    anonymized.push_str(&facet[path.len()..]);
+    anonymized
+}
+
+/// Map facets lazily to their topics, i.e. with any date-like suffix stripped.
+///
+/// Collect the resulting topics into a set for deduplication.
+pub fn topics<'a, F, I>(facets: I) -> impl Iterator<Item = &'a str>
+where
+    F: Facet + 'a,
+    I: IntoIterator<Item = &'a F>,
+{
+    facets.into_iter().map(FacetRef::strip_date_like_suffix)
+}
+
+/// Count facets by their first path segment.
+///
+/// Facets without any [`segments()`], i.e. empty facets and facets that
+/// only consist of a date-like suffix, are counted with an empty key.
+#[must_use]
+pub fn count_by_top_segment<F: Facet>(facets: &[F]) -> BTreeMap<&str, usize> {
+    let mut counts = BTreeMap::new();
+    for facet in facets {
+        let top_segment = facet.segments().next().unwrap_or_default();
+        *counts.entry(top_segment).or_default() += 1;
+    }
+    counts
+}
+
+/// Collect the distinct first path segments of facets as new facets.
+///
+/// Facets without any [`segments()`] and empty first segments are
+/// excluded. The categories are returned in ascending order.
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn top_level_categories<F: Facet>(facets: &[F]) -> Vec<CompactFacet> {
+    facets
+        .iter()
+        .filter_map(|facet| facet.segments().next())
+        .filter(|segment| !segment.is_empty())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(CompactFacet::from_str)
+        .collect()
+}
+
+/// Convert a facet into a facet of a different type.
+///
+/// A blanket `impl<F: Facet, G: Facet> From<&F> for G` is not possible,
+/// because it would conflict with the reflexive `impl<T> From<T> for T`
+/// of the standard library and violate the orphan rules for foreign types.
+#[must_use]
+pub fn facet_from<G: Facet, F: Facet>(src: &F) -> G {
+    G::from_str(src.as_ref())
+}
+
+/// Rewrite a collection of facets by applying a closure to each facet.
+///
+/// Facets for which the closure returns `None` are dropped. The order
+/// of the remaining facets is preserved.
+#[cfg(feature = "compact_str")]
+#[must_use]
+pub fn rewrite<F, G>(facets: Vec<F>, mut f: G) -> Vec<CompactFacet>
+where
+    F: Facet,
+    G: FnMut(&str) -> Option<CompactFacet>,
+{
+    facets
+        .into_iter()
+        .filter_map(|facet| f(facet.as_ref()))
+        .collect()
+}
+
+/// Summarize a set of facets in a single line for display.
+///
+/// The summary reads like `42 facets, 30 dated, 3 invalid dates, max depth 4`.
+/// Only facets with a valid date suffix are counted as dated.
+///
+/// See also: [`FacetStats::summary()`]
+#[must_use]
+pub fn summarize_set<F: Facet>(facets: &[F]) -> String {
+    let mut stats = FacetStats::new();
+    for facet in facets {
+        stats.record(facet.as_ref());
+    }
+    format!(
+        "{count} facets, {dated} dated, {invalid_dates} invalid dates, max depth {max_depth}",
+        count = facets.len(),
+        dated = stats.date_suffix_count(),
+        invalid_dates = stats.invalid_date_suffix_count(),
+        max_depth = stats.max_depth().unwrap_or_default(),
+    )
+}
+
+/// Build a [`FacetTree`] from the given facets.
+///
+/// Facets are attached to the node of their last segment, including
+/// their date-like suffix.
+#[must_use]
+pub fn build_tree<F: FacetRef>(facets: &[F]) -> FacetTree<'_, F> {
+    FacetTree::build(facets)
+}
+
+/// Source of the current date
+///
+/// Allows to inject a fixed date for testing.
+pub trait Clock {
+    /// The current calendar date.
+    #[must_use]
+    fn today(&self) -> Date;
 }
 
-/// Check if the given facet is empty.
-#[must_use]
-pub fn is_empty(facet: &str) -> bool {
-    debug_assert!(is_valid(facet));
-    facet.is_empty()
-}
+/// The system clock
+///
+/// Returns the current calendar date in UTC.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn today(&self) -> Date {
+        time::OffsetDateTime::now_utc().date()
+    }
+}
+
+mod charset;
+pub use self::charset::CharSet;
+
+mod date_suffix_cache;
+pub use self::date_suffix_cache::DateSuffixCache;
+
+#[cfg(all(feature = "compact_str", feature = "serde"))]
+pub mod joined;
+
+mod keyed;
+pub use self::keyed::Keyed;
+
+mod policy;
+pub use self::policy::{FacetPolicy, PolicyViolation};
+
+mod prefix_matcher;
+pub use self::prefix_matcher::PrefixMatcher;
+
+#[cfg(feature = "compact_str")]
+mod scanner;
+#[cfg(feature = "compact_str")]
+pub use self::scanner::FacetScanner;
+
+mod stats;
+pub use self::stats::FacetStats;
+
+mod tree;
+pub use self::tree::FacetTree;
+
+/// Read-only operations on facets
+pub trait FacetRef: AsRef<str> {
+    /// [`is_valid()`]
+    #[must_use]
+    fn is_valid(&self) -> bool {
+        is_valid(self.as_ref())
+    }
+
+    /// [`is_valid_non_dateonly()`]
+    #[must_use]
+    fn is_valid_non_dateonly(&self) -> bool {
+        is_valid_non_dateonly(self.as_ref())
+    }
+
+    /// [`is_empty()`]
+    #[must_use]
+    fn is_empty(&self) -> bool {
+        is_empty(self.as_ref())
+    }
+
+    /// [`has_date_like_suffix()`]
+    #[must_use]
+    fn has_date_like_suffix(&self) -> bool {
+        has_date_like_suffix(self.as_ref())
+    }
+
+    /// [`try_split_into_prefix_and_date_like_suffix()`]
+    #[must_use]
+    fn try_split_into_prefix_and_date_like_suffix(&self) -> Option<(&str, &str)> {
+        try_split_into_prefix_and_date_like_suffix(self.as_ref())
+    }
+
+    /// [`has_temporal_suffix()`]
+    #[must_use]
+    fn has_temporal_suffix(&self) -> bool {
+        has_temporal_suffix(self.as_ref())
+    }
+
+    /// [`date_like_suffix()`]
+    #[must_use]
+    fn date_like_suffix(&self, include_separator: bool) -> Option<&str> {
+        date_like_suffix(self.as_ref(), include_separator)
+    }
+
+    /// [`try_split_into_prefix_and_parse_date_suffix()`]
+    #[must_use]
+    fn try_split_into_prefix_and_parse_date_suffix(&self) -> Option<(&str, Option<Date>)> {
+        try_split_into_prefix_and_parse_date_suffix(self.as_ref())
+    }
+
+    /// [`try_split_into_prefix_and_ordinal_date()`]
+    #[must_use]
+    fn try_split_into_prefix_and_ordinal_date(&self) -> Option<(&str, Date)> {
+        try_split_into_prefix_and_ordinal_date(self.as_ref())
+    }
 
-/// Check for a date-like suffix in the facet.
-#[must_use]
-pub fn has_date_like_suffix(facet: &str) -> bool {
-    debug_assert!(is_valid(facet));
-    date_like_suffix_regex().is_match(facet.as_bytes())
-}
+    /// [`date_suffix()`]
+    #[must_use]
+    fn date_suffix(&self) -> Option<Date> {
+        date_suffix(self.as_ref())
+    }
 
-/// Split a facet into a prefix and the date-like suffix.
-#[must_use]
-pub fn try_split_into_prefix_and_date_like_suffix(facet: &str) -> Option<(&str, &str)> {
-    debug_assert!(is_valid(facet));
-    if facet.len() < DATE_LIKE_SUFFIX_LEN {
-        return None;
+    /// [`date_suffix_components()`]
+    #[must_use]
+    fn date_suffix_components(&self) -> Option<(i32, u8, u8)> {
+        date_suffix_components(self.as_ref())
     }
-    let prefix_len = facet.len() - DATE_LIKE_SUFFIX_LEN;
-    let date_suffix = &facet[prefix_len..];
-    if !date_suffix.is_ascii() {
-        return None;
+
+    /// [`explain_date_suffix()`]
+    #[must_use]
+    fn explain_date_suffix(&self) -> DateSuffixReport<'_> {
+        explain_date_suffix(self.as_ref())
     }
-    let prefix = &facet[..prefix_len];
-    (prefix, date_suffix).into()
-}
 
-/// Split a facet into a prefix and parse the date suffix.
-#[must_use]
-pub fn try_split_into_prefix_and_parse_date_suffix(facet: &str) -> Option<(&str, Option<Date>)> {
-    debug_assert!(is_valid(facet));
-    let (prefix, date_suffix) = try_split_into_prefix_and_date_like_suffix(facet)?;
-    let date = Date::parse(date_suffix, DATE_SUFFIX_FORMAT).ok();
-    (prefix, date).into()
-}
+    /// [`date_suffix_age()`]
+    #[must_use]
+    fn date_suffix_age(&self, today: Date) -> Option<time::Duration> {
+        date_suffix_age(self.as_ref(), today)
+    }
 
-const DATE_SUFFIX_FORMAT: &[FormatItem<'static>] = format_description!("@[year][month][day]");
+    /// [`date_suffix_iso_week()`]
+    #[must_use]
+    fn date_suffix_iso_week(&self) -> Option<(i32, u8)> {
+        date_suffix_iso_week(self.as_ref())
+    }
 
-// @yyyyMMdd
-const DATE_LIKE_SUFFIX_LEN: usize = 1 + 8;
+    /// [`classify()`]
+    #[must_use]
+    fn classify(&self) -> FacetKind {
+        classify(self.as_ref())
+    }
 
-const DATE_LIKE_SUFFIX_REGEX_STR: &str = r"(^|[^\s])@\d{8}$";
+    /// [`decompose()`]
+    #[must_use]
+    fn decompose(&self) -> FacetParts<'_> {
+        decompose(self.as_ref())
+    }
 
-static DATE_LIKE_SUFFIX_REGEX: OnceCell<Regex> = OnceCell::new();
+    /// [`describe()`]
+    #[must_use]
+    fn describe(&self) -> String {
+        describe(self.as_ref())
+    }
 
-#[must_use]
-fn date_like_suffix_regex() -> &'static Regex {
-    // The '@' separator of the date-like digits must not be preceded by
-    // a whitespace i.e. the facet either equals the date-like suffix
-    // or the separator is preceded by a non-whitespace character.
-    DATE_LIKE_SUFFIX_REGEX.get_or_init(|| DATE_LIKE_SUFFIX_REGEX_STR.parse().unwrap())
-}
+    /// [`date_suffix_formatted()`]
+    #[must_use]
+    fn date_suffix_formatted(
+        &self,
+        format: &[FormatItem<'_>],
+    ) -> Option<Result<String, time::error::Format>> {
+        date_suffix_formatted(self.as_ref(), format)
+    }
 
-const INVALID_DATE_LIKE_SUFFIX_REGEX_STR: &str = r"[\s]+@\d{8}$";
+    /// [`strip_date_like_suffix()`]
+    #[must_use]
+    fn strip_date_like_suffix(&self) -> &str {
+        strip_date_like_suffix(self.as_ref())
+    }
 
-static INVALID_DATE_LIKE_SUFFIX_REGEX: OnceCell<Regex> = OnceCell::new();
+    /// [`segments()`]
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        segments(self.as_ref())
+    }
 
-#[must_use]
-fn invalid_date_like_suffix_regex() -> &'static Regex {
-    // Reject facets with date-like suffixes that are preceded by a whitespace character
-    INVALID_DATE_LIKE_SUFFIX_REGEX
-        .get_or_init(|| INVALID_DATE_LIKE_SUFFIX_REGEX_STR.parse().unwrap())
-}
+    /// [`segment()`]
+    #[must_use]
+    fn segment(&self, index: usize) -> Option<&str> {
+        segment(self.as_ref(), index)
+    }
 
-/// Check a string for an invalid date-like suffix.
-#[must_use]
-pub fn has_invalid_date_like_suffix(facet: &str) -> bool {
-    debug_assert!(is_valid(facet));
-    invalid_date_like_suffix_regex().is_match(facet.as_bytes())
+    /// [`try_split_off_version_segment()`]
+    #[must_use]
+    fn try_split_off_version_segment(&self) -> (&str, Option<u32>) {
+        try_split_off_version_segment(self.as_ref())
+    }
+
+    /// [`segments_with()`]
+    fn segments_with(&self, sep: char) -> impl Iterator<Item = &str> {
+        segments_with(self.as_ref(), sep)
+    }
+
+    /// [`starts_with_path_prefix()`]
+    #[must_use]
+    fn starts_with_path_prefix(&self, prefix: &str) -> bool {
+        starts_with_path_prefix(self.as_ref(), prefix)
+    }
+
+    /// [`starts_with_path_prefix_with()`]
+    #[must_use]
+    fn starts_with_path_prefix_with(&self, prefix: &str, sep: char) -> bool {
+        starts_with_path_prefix_with(self.as_ref(), prefix, sep)
+    }
+
+    /// [`strip_path_prefix()`]
+    #[must_use]
+    fn strip_path_prefix(&self, prefix: &str) -> Option<&str> {
+        strip_path_prefix(self.as_ref(), prefix)
+    }
+
+    /// [`strip_path_prefix_with()`]
+    #[must_use]
+    fn strip_path_prefix_with(&self, prefix: &str, sep: char) -> Option<&str> {
+        strip_path_prefix_with(self.as_ref(), prefix, sep)
+    }
+
+    /// [`is_valid_charset()`]
+    #[must_use]
+    fn is_valid_charset(&self, allowed: &CharSet) -> bool {
+        is_valid_charset(self.as_ref(), allowed)
+    }
+
+    /// [`is_reserved()`]
+    #[must_use]
+    fn is_reserved(&self, reserved_prefixes: &[&str]) -> bool {
+        is_reserved(self.as_ref(), reserved_prefixes)
+    }
+
+    /// [`detect_separator_style()`]
+    #[must_use]
+    fn detect_separator_style(&self) -> SeparatorStyle {
+        detect_separator_style(self.as_ref())
+    }
+
+    /// [`render_path()`]
+    #[must_use]
+    fn render_path(&self, separator: &str) -> Cow<'_, str> {
+        render_path(self.as_ref(), separator)
+    }
+
+    /// [`segment_spans()`]
+    fn segment_spans(&self) -> impl Iterator<Item = (Range<usize>, &str)> {
+        segment_spans(self.as_ref())
+    }
+
+    /// [`segment_spans_with()`]
+    fn segment_spans_with(&self, sep: char) -> impl Iterator<Item = (Range<usize>, &str)> {
+        segment_spans_with(self.as_ref(), sep)
+    }
+
+    /// [`ancestors()`]
+    fn ancestors(&self) -> impl Iterator<Item = &str> {
+        ancestors(self.as_ref())
+    }
+
+    /// [`ancestors_with()`]
+    fn ancestors_with(&self, sep: char) -> impl Iterator<Item = &str> {
+        ancestors_with(self.as_ref(), sep)
+    }
+
+    /// [`separator_count()`]
+    #[must_use]
+    fn separator_count(&self) -> usize {
+        separator_count(self.as_ref())
+    }
+
+    /// [`separator_count_with()`]
+    #[must_use]
+    fn separator_count_with(&self, sep: char) -> usize {
+        separator_count_with(self.as_ref(), sep)
+    }
+
+    /// [`to_csv_field()`]
+    #[must_use]
+    fn to_csv_field(&self) -> Cow<'_, str> {
+        to_csv_field(self.as_ref())
+    }
+
+    /// [`sanitize_for_filename()`]
+    #[must_use]
+    fn sanitize_for_filename(&self) -> String {
+        sanitize_for_filename(self.as_ref())
+    }
+
+    /// [`to_slug()`]
+    #[must_use]
+    fn to_slug(&self) -> String {
+        to_slug(self.as_ref())
+    }
+
+    /// [`display_label()`]
+    #[must_use]
+    fn display_label(&self) -> String {
+        display_label(self.as_ref())
+    }
+
+    /// [`encode_binary()`]
+    fn encode_binary(&self, out: &mut Vec<u8>) {
+        encode_binary(self.as_ref(), out);
+    }
+
+    /// [`breadcrumbs()`]
+    #[must_use]
+    fn breadcrumbs(&self, separator: &str, with_date: bool) -> String {
+        breadcrumbs(self.as_ref(), separator, with_date)
+    }
+
+    /// [`to_anonymized()`]
+    #[must_use]
+    fn to_anonymized(&self) -> String {
+        to_anonymized(self.as_ref())
+    }
+
+    /// Convert into a facet of a different type.
+    ///
+    /// See also: [`facet_from()`]
+    #[must_use]
+    fn to_facet<G: Facet>(&self) -> G {
+        G::from_str(self.as_ref())
+    }
 }
 
-/// Common trait for facets
-pub trait Facet: AsRef<str> + Default + PartialEq + Ord + Sized {
+/// Construction of facets
+pub trait FacetBuild: Sized {
     /// Crate a facet from a borrowed string slice.
     #[must_use]
     fn from_str(facet: &str) -> Self {
@@ -117,9 +2447,10 @@ pub trait Facet: AsRef<str> + Default + PartialEq + Ord + Sized {
     ///
     /// # Errors
     ///
-    /// Returns an error if formatting of the given `date` fails.
+    /// Returns an error if formatting of the given `date` fails, i.e.
+    /// if the year is not in the range `0..=9999`.
     fn from_prefix_with_date_suffix(prefix: &str, date: Date) -> Result<Self, time::error::Format> {
-        let suffix = date.format(DATE_SUFFIX_FORMAT)?;
+        let suffix = format_date_suffix(date)?;
         Ok(Self::from_string(format!("{prefix}{suffix}")))
     }
 
@@ -130,51 +2461,71 @@ pub trait Facet: AsRef<str> + Default + PartialEq + Ord + Sized {
     ///
     /// # Errors
     ///
-    /// Returns an error if formatting of the given `date` fails.
+    /// Returns an error if formatting of the given `date` fails, i.e.
+    /// if the year is not in the range `0..=9999`.
     fn from_prefix_args_with_date_suffix(
         prefix_args: fmt::Arguments<'_>,
         date: Date,
     ) -> Result<Self, time::error::Format> {
-        let suffix = date.format(DATE_SUFFIX_FORMAT)?;
+        let suffix = format_date_suffix(date)?;
         Ok(Self::from_string(format!("{prefix_args}{suffix}")))
     }
 
-    /// [`is_valid()`]
-    #[must_use]
-    fn is_valid(&self) -> bool {
-        is_valid(self.as_ref())
-    }
-
-    /// [`is_empty()`]
-    #[must_use]
-    fn is_empty(&self) -> bool {
-        is_empty(self.as_ref())
-    }
-
-    /// [`has_date_like_suffix()`]
-    #[must_use]
-    fn has_date_like_suffix(&self) -> bool {
-        has_date_like_suffix(self.as_ref())
-    }
-
-    /// [`try_split_into_prefix_and_date_like_suffix()`]
-    #[must_use]
-    fn try_split_into_prefix_and_date_like_suffix(&self) -> Option<(&str, &str)> {
-        try_split_into_prefix_and_date_like_suffix(self.as_ref())
+    /// Concatenate a prefix and an ordinal [`Date`] suffix to a facet.
+    ///
+    /// See also: [`try_split_into_prefix_and_ordinal_date()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if formatting of the given `date` fails, i.e.
+    /// if the year is not in the range `0..=9999`.
+    fn from_prefix_with_ordinal_date_suffix(
+        prefix: &str,
+        date: Date,
+    ) -> Result<Self, time::error::Format> {
+        let suffix = format_ordinal_date_suffix(date)?;
+        Ok(Self::from_string(format!("{prefix}{suffix}")))
     }
 
-    /// [`try_split_into_prefix_and_parse_date_suffix()`]
-    #[must_use]
-    fn try_split_into_prefix_and_parse_date_suffix(&self) -> Option<(&str, Option<Date>)> {
-        try_split_into_prefix_and_parse_date_suffix(self.as_ref())
+    /// Concatenate a prefix and the current date of the given clock to a facet.
+    ///
+    /// See also: [`FacetBuild::from_prefix_with_date_suffix()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if formatting of the current date fails.
+    fn from_prefix_with_today<C: Clock>(
+        prefix: &str,
+        clock: &C,
+    ) -> Result<Self, time::error::Format> {
+        Self::from_prefix_with_date_suffix(prefix, clock.today())
     }
 }
 
+/// Common trait for facets
+///
+/// Combines [`FacetRef`] and [`FacetBuild`]. Implementations need to
+/// implement all three traits, i.e. `impl Facet for MyFacet {}`.
+///
+/// All facet types of this crate hash purely on their UTF-8 bytes, i.e.
+/// identically to the corresponding [`str`]. Facets of different types with
+/// identical bytes are therefore interchangeable as keys in hash maps.
+/// They are ordered consistently by [`total_cmp()`].
+///
+/// The same applies to labels, i.e. a facet and a label with identical
+/// bytes hash identically. No domain tag is mixed into the hash, because
+/// it would break looking up facets by [`str`] through [`Borrow`].
+/// Keys of a map that contains both facets and labels must therefore
+/// be disambiguated explicitly, e.g. by an `enum` with a derived [`Hash`].
+pub trait Facet: FacetRef + FacetBuild + Default + PartialEq + Ord {}
+
 /// Facet with a `CompactString` representation
+#[cfg(feature = "compact_str")]
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(clippy::module_name_repetitions)]
 pub struct CompactFacet(CompactString);
 
+#[cfg(feature = "compact_str")]
 impl CompactFacet {
     /// Create a new facet.
     ///
@@ -183,14 +2534,128 @@ impl CompactFacet {
     pub const fn new(inner: CompactString) -> Self {
         Self(inner)
     }
+
+    /// Reset the facet to empty while retaining its capacity.
+    pub fn clear(&mut self) {
+        // CompactString doesn't provide a safe method for truncating
+        // the contents at once.
+        while self.0.pop().is_some() {}
+    }
+
+    /// Replace the contents of the facet, reusing the allocated buffer.
+    ///
+    /// Allows to reuse a single facet in tight loops without allocating
+    /// for each iteration.
+    ///
+    /// The argument is not validated.
+    pub fn set_from(&mut self, facet: &str) {
+        self.clear();
+        self.0.push_str(facet);
+    }
+
+    /// Parse facets from text with one facet per line.
+    ///
+    /// Blank lines and comment lines that start with `#` are skipped.
+    /// All other lines are validated verbatim, i.e. leading/trailing
+    /// whitespace is not trimmed. Invalid lines are reported together
+    /// with their 1-based line number.
+    #[must_use]
+    pub fn from_lines(text: &str) -> Vec<Result<Self, (usize, FacetError)>> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim_start();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(index, line)| {
+                validate(line)
+                    .map(|()| Self::from_str(line))
+                    .map_err(|err| (index + 1, err))
+            })
+            .collect()
+    }
+
+    /// Parse facets from the values of a repeated key in a URL query string.
+    ///
+    /// The query must not start with `?`, e.g. `facet=genre%2Fhouse&facet=mood%2Fchill`.
+    /// Both keys and values are decoded like `application/x-www-form-urlencoded`,
+    /// i.e. `+` is decoded as a space and an encoded `%2F` is decoded as
+    /// [`PATH_SEPARATOR`]. Pairs with other keys are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FacetFromBytesError`] if a decoded value is either not
+    /// valid UTF-8 or not a valid facet.
+    pub fn from_query_string(query: &str, key: &str) -> Result<Vec<Self>, FacetFromBytesError> {
+        query
+            .split('&')
+            .filter_map(|key_value| {
+                let (encoded_key, encoded_value) = key_value.split_once('=')?;
+                decode_query_component(encoded_key)
+                    .eq(key.bytes())
+                    .then_some(encoded_value)
+            })
+            .map(|encoded_value| {
+                let value = String::from_utf8(decode_query_component(encoded_value).collect())
+                    .map_err(|err| err.utf8_error())?;
+                validate(&value)?;
+                Ok(Self::from_string(value))
+            })
+            .collect()
+    }
+
+    /// Concatenate a prefix and a date suffix given by year, month, and day.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DatedFacetError`] if either the prefix is invalid, the
+    /// calendar date is invalid, or if the date could not be encoded.
+    pub fn from_prefix_with_ymd(
+        prefix: &str,
+        year: i32,
+        month: u8,
+        day: u8,
+    ) -> Result<Self, DatedFacetError> {
+        validate(prefix).map_err(DatedFacetError::InvalidPrefix)?;
+        let month = time::Month::try_from(month).map_err(DatedFacetError::InvalidDate)?;
+        let date =
+            Date::from_calendar_date(year, month, day).map_err(DatedFacetError::InvalidDate)?;
+        let facet = Self::from_prefix_with_date_suffix(prefix, date)?;
+        Ok(facet)
+    }
+
+    /// Read a facet from an environment variable.
+    ///
+    /// Returns `Ok(None)` if the variable is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FacetFromEnvError`] if the value is either not valid
+    /// Unicode or not a valid facet.
+    #[cfg(feature = "std")]
+    pub fn from_env(var: &str) -> Result<Option<Self>, FacetFromEnvError> {
+        let value = match std::env::var(var) {
+            Ok(value) => value,
+            Err(std::env::VarError::NotPresent) => return Ok(None),
+            Err(std::env::VarError::NotUnicode(value)) => {
+                return Err(FacetFromEnvError::NotUnicode(value));
+            }
+        };
+        if let Err(error) = validate(&value) {
+            return Err(FacetFromEnvError::InvalidFacet { value, error });
+        }
+        Ok(Some(Self::from_string(value)))
+    }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactString> for CompactFacet {
     fn from(from: CompactString) -> Self {
         Self::new(from)
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactFacet> for CompactString {
     fn from(from: CompactFacet) -> Self {
         let CompactFacet(inner) = from;
@@ -198,12 +2663,58 @@ impl From<CompactFacet> for CompactString {
     }
 }
 
+#[cfg(feature = "compact_str")]
+impl TryFrom<&[u8]> for CompactFacet {
+    type Error = FacetFromBytesError;
+
+    fn try_from(from: &[u8]) -> Result<Self, Self::Error> {
+        let facet = std::str::from_utf8(from)?;
+        validate(facet)?;
+        Ok(Self::from_str(facet))
+    }
+}
+
+#[cfg(feature = "compact_str")]
+// The reverse `PartialEq<CompactFacet> for CompactString` is already
+// covered by the blanket `impl<T: AsRef<str>> PartialEq<T>` of `compact_str`.
+impl PartialEq<CompactString> for CompactFacet {
+    fn eq(&self, other: &CompactString) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "compact_str")]
 impl AsRef<str> for CompactFacet {
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
+// Consistent with the hashing contract of all facet types, see `Facet`.
+#[cfg(feature = "compact_str")]
+impl Borrow<str> for CompactFacet {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Borrow the facet as an [`OsStr`], e.g. for passing it to [`std::path::Path::join()`].
+///
+/// Slashes `/` in facets might be interpreted as path separators by the
+/// file system APIs that receive the resulting [`OsStr`].
+///
+/// Calls of `facet.as_ref()` become ambiguous if the target type cannot
+/// be inferred, e.g. when comparing the result with a string literal.
+/// Dereference the facet with `&*facet` or call `AsRef::<str>::as_ref()`
+/// explicitly in those cases.
+#[cfg(feature = "compact_str")]
+impl AsRef<OsStr> for CompactFacet {
+    fn as_ref(&self) -> &OsStr {
+        OsStr::new(self.0.as_str())
+    }
+}
+
+#[cfg(feature = "compact_str")]
 impl Deref for CompactFacet {
     type Target = str;
 
@@ -212,7 +2723,42 @@ impl Deref for CompactFacet {
     }
 }
 
-impl Facet for CompactFacet {
+/// Regular expression that matches all [valid](is_valid) facets.
+#[cfg(all(feature = "compact_str", feature = "schemars"))]
+const VALID_FACET_REGEX_STR: &str = r"^([^/\s]([\s\S]*\S)?)?$";
+
+/// The schema restricts the syntax by a `pattern`, but omits `maxLength`.
+///
+/// Facets are not limited in length. An optional limit is a matter of
+/// [`FacetPolicy`], and it is measured in bytes while `maxLength`
+/// counts characters.
+#[cfg(all(feature = "compact_str", feature = "schemars"))]
+impl schemars::JsonSchema for CompactFacet {
+    fn schema_name() -> String {
+        "Facet".to_owned()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            string: Some(Box::new(schemars::schema::StringValidation {
+                pattern: Some(VALID_FACET_REGEX_STR.to_owned()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl Facet for CompactFacet {}
+
+#[cfg(feature = "compact_str")]
+impl FacetRef for CompactFacet {}
+
+#[cfg(feature = "compact_str")]
+impl FacetBuild for CompactFacet {
     fn from_str(facet: &str) -> Self {
         Self(facet.into())
     }
@@ -226,7 +2772,7 @@ impl Facet for CompactFacet {
     }
 
     fn from_prefix_with_date_suffix(prefix: &str, date: Date) -> Result<Self, time::error::Format> {
-        let suffix = date.format(DATE_SUFFIX_FORMAT)?;
+        let suffix = format_date_suffix(date)?;
         Ok(Self(format_compact!("{prefix}{suffix}")))
     }
 
@@ -234,7 +2780,7 @@ impl Facet for CompactFacet {
         prefix_args: fmt::Arguments<'_>,
         date: Date,
     ) -> Result<Self, time::error::Format> {
-        let suffix = date.format(DATE_SUFFIX_FORMAT)?;
+        let suffix = format_date_suffix(date)?;
         Ok(Self(format_compact!("{prefix_args}{suffix}")))
     }
 }
@@ -273,6 +2819,23 @@ impl AsRef<str> for StdFacet {
     }
 }
 
+// Consistent with the hashing contract of all facet types, see `Facet`.
+impl Borrow<str> for StdFacet {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Borrow the facet as an [`OsStr`], e.g. for passing it to [`std::path::Path::join()`].
+///
+/// Slashes `/` in facets might be interpreted as path separators by the
+/// file system APIs that receive the resulting [`OsStr`].
+impl AsRef<OsStr> for StdFacet {
+    fn as_ref(&self) -> &OsStr {
+        OsStr::new(self.0.as_str())
+    }
+}
+
 impl Deref for StdFacet {
     type Target = str;
 
@@ -281,7 +2844,11 @@ impl Deref for StdFacet {
     }
 }
 
-impl Facet for StdFacet {
+impl Facet for StdFacet {}
+
+impl FacetRef for StdFacet {}
+
+impl FacetBuild for StdFacet {
     fn from_str(facet: &str) -> Self {
         Self(facet.into())
     }
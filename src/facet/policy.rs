@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use thiserror::Error;
+
+use super::{
+    date_suffix, is_valid_charset, segments, starts_with_path_prefix, validate, CharSet, FacetError,
+};
+
+/// Reasons why a facet violates a [`FacetPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[allow(clippy::module_name_repetitions)]
+#[non_exhaustive]
+pub enum PolicyViolation {
+    /// The facet is invalid.
+    #[error(transparent)]
+    InvalidFacet(#[from] FacetError),
+
+    /// The facet exceeds the maximum length in bytes.
+    #[error("too long: {len} > {max_len} bytes")]
+    TooLong {
+        /// The length of the facet in bytes.
+        len: usize,
+
+        /// The maximum length in bytes.
+        max_len: usize,
+    },
+
+    /// The facet exceeds the maximum number of segments.
+    #[error("too deep: {depth} > {max_depth} segments")]
+    TooDeep {
+        /// The number of segments of the facet.
+        depth: usize,
+
+        /// The maximum number of segments.
+        max_depth: usize,
+    },
+
+    /// The facet is not located under any of the allowed roots.
+    #[error("root not allowed")]
+    RootNotAllowed,
+
+    /// The facet contains characters that are not allowed.
+    #[error("invalid characters")]
+    InvalidCharset,
+
+    /// The facet has no valid date suffix.
+    #[error("missing date suffix")]
+    MissingDateSuffix,
+}
+
+/// Declarative constraints for facets
+///
+/// All constraints are optional and disabled by default, i.e. the
+/// default policy only requires that facets are valid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct FacetPolicy {
+    /// The maximum length of a facet in bytes, including the date suffix.
+    pub max_len: Option<usize>,
+
+    /// The maximum number of [`segments()`](super::segments).
+    pub max_depth: Option<usize>,
+
+    /// Path prefixes of which each facet must start with at least one,
+    /// see [`starts_with_path_prefix()`](super::starts_with_path_prefix).
+    ///
+    /// All facets are allowed if empty.
+    pub allowed_roots: Vec<String>,
+
+    /// The allowed characters, see [`is_valid_charset()`](super::is_valid_charset).
+    pub charset: Option<CharSet>,
+
+    /// Require a valid date suffix.
+    pub require_date_suffix: bool,
+}
+
+impl FacetPolicy {
+    /// Check a facet against all constraints of the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns all violations of the policy. Invalid facets are rejected
+    /// with a single [`PolicyViolation::InvalidFacet`] without checking
+    /// any further constraints.
+    pub fn check(&self, facet: &str) -> Result<(), Vec<PolicyViolation>> {
+        validate(facet).map_err(|err| vec![err.into()])?;
+        let Self {
+            max_len,
+            max_depth,
+            allowed_roots,
+            charset,
+            require_date_suffix,
+        } = self;
+        let mut violations = Vec::new();
+        if let Some(max_len) = *max_len {
+            let len = facet.len();
+            if len > max_len {
+                violations.push(PolicyViolation::TooLong { len, max_len });
+            }
+        }
+        if let Some(max_depth) = *max_depth {
+            let depth = segments(facet).count();
+            if depth > max_depth {
+                violations.push(PolicyViolation::TooDeep { depth, max_depth });
+            }
+        }
+        if !allowed_roots.is_empty()
+            && !allowed_roots
+                .iter()
+                .any(|root| starts_with_path_prefix(facet, root))
+        {
+            violations.push(PolicyViolation::RootNotAllowed);
+        }
+        if let Some(charset) = charset {
+            if !is_valid_charset(facet, charset) {
+                violations.push(PolicyViolation::InvalidCharset);
+            }
+        }
+        if *require_date_suffix && date_suffix(facet).is_none() {
+            violations.push(PolicyViolation::MissingDateSuffix);
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::collections::HashMap;
+
+use super::segments;
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<String, usize>,
+    prefix_index: Option<usize>,
+}
+
+/// Match facets against a set of path prefixes
+///
+/// The prefixes are stored in a trie of path segments. Finding the longest
+/// matching prefix of a facet only depends on the number of segments of
+/// the facet and not on the number of prefixes.
+///
+/// The matching is segment-aware and consistent with
+/// [`starts_with_path_prefix()`](super::starts_with_path_prefix).
+#[derive(Debug, Default)]
+pub struct PrefixMatcher {
+    prefixes: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+impl PrefixMatcher {
+    /// Create a new matcher from the given prefixes.
+    #[must_use]
+    pub fn new<'a>(prefixes: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut matcher = Self::default();
+        for prefix in prefixes {
+            matcher.insert(prefix);
+        }
+        matcher
+    }
+
+    /// Add a prefix.
+    pub fn insert(&mut self, prefix: &str) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::default());
+        }
+        let mut node_index = 0;
+        for segment in segments(prefix) {
+            let next_index = self.nodes.len();
+            node_index = *self.nodes[node_index]
+                .children
+                .entry(segment.into())
+                .or_insert(next_index);
+            if node_index == next_index {
+                self.nodes.push(Node::default());
+            }
+        }
+        let node = &mut self.nodes[node_index];
+        if node.prefix_index.is_none() {
+            node.prefix_index = Some(self.prefixes.len());
+            self.prefixes.push(prefix.to_owned());
+        }
+    }
+
+    /// Find the longest prefix that matches the given facet.
+    ///
+    /// If multiple prefixes consist of the same segments, i.e. if they only
+    /// differ by their date-like suffix, then the first one is returned.
+    #[must_use]
+    pub fn longest_match(&self, facet: &str) -> Option<&str> {
+        let mut node = self.nodes.first()?;
+        let mut longest_match = node.prefix_index;
+        for segment in segments(facet) {
+            let Some(&next_index) = node.children.get(segment) else {
+                break;
+            };
+            node = &self.nodes[next_index];
+            longest_match = node.prefix_index.or(longest_match);
+        }
+        longest_match.map(|index| self.prefixes[index].as_str())
+    }
+}
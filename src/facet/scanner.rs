@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::fmt;
+
+use super::{try_split_suffix, CompactFacet, FacetBuild as _, FacetError, DATE_LIKE_SUFFIX_LEN};
+
+/// Assemble a facet incrementally
+///
+/// The validity of the facet can be checked at any time without
+/// re-scanning the whole buffer.
+#[derive(Debug, Clone, Default)]
+pub struct FacetScanner {
+    buffer: String,
+    first_char: Option<char>,
+    last_char: Option<char>,
+}
+
+impl FacetScanner {
+    /// Create an empty scanner.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            first_char: None,
+            last_char: None,
+        }
+    }
+
+    /// Append a string.
+    pub fn push_str(&mut self, s: &str) {
+        let mut chars = s.chars();
+        let Some(first_char) = chars.next() else {
+            return;
+        };
+        self.first_char.get_or_insert(first_char);
+        self.last_char = chars.next_back().or(Some(first_char));
+        self.buffer.push_str(s);
+    }
+
+    /// Append a single character.
+    pub fn push_char(&mut self, c: char) {
+        self.first_char.get_or_insert(c);
+        self.last_char = Some(c);
+        self.buffer.push(c);
+    }
+
+    /// The contents that have been pushed so far.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Validate the current contents.
+    ///
+    /// Only the first and the last characters are inspected, i.e. the
+    /// costs don't depend on the length of the contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`FacetError`] as [`validate()`](super::validate).
+    pub fn validate(&self) -> Result<(), FacetError> {
+        if self.first_char.is_some_and(char::is_whitespace)
+            || self.last_char.is_some_and(char::is_whitespace)
+        {
+            return Err(FacetError::LeadingOrTrailingWhitespace);
+        }
+        if self.first_char == Some(super::PATH_SEPARATOR) {
+            return Err(FacetError::LeadingSlash);
+        }
+        if self.has_invalid_date_like_suffix() {
+            return Err(FacetError::InvalidDateLikeSuffix);
+        }
+        Ok(())
+    }
+
+    /// Check if the current contents are a valid facet.
+    ///
+    /// See also: [`FacetScanner::validate()`]
+    #[must_use]
+    pub fn is_currently_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    fn has_invalid_date_like_suffix(&self) -> bool {
+        let Some((prefix, suffix)) = try_split_suffix(&self.buffer, DATE_LIKE_SUFFIX_LEN, '@')
+        else {
+            return false;
+        };
+        suffix[1..].bytes().all(|b| b.is_ascii_digit())
+            && prefix.chars().next_back().is_some_and(char::is_whitespace)
+    }
+
+    /// Finish the facet.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FacetError`] if the contents are not a valid facet.
+    pub fn finish(self) -> Result<CompactFacet, FacetError> {
+        self.validate()?;
+        Ok(CompactFacet::from_string(self.buffer))
+    }
+}
+
+impl fmt::Write for FacetScanner {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push_char(c);
+        Ok(())
+    }
+}
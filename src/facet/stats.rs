@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{date_suffix, has_date_like_suffix, segments, validate};
+
+/// Aggregate statistics about facets
+///
+/// Facets are recorded one at a time without allocating memory per facet.
+/// Only facets that pass [`validate()`](super::validate) are inspected
+/// further, all other facets are only counted as invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FacetStats {
+    valid_count: usize,
+    invalid_count: usize,
+    date_suffix_count: usize,
+    invalid_date_suffix_count: usize,
+    depth_histogram: Vec<usize>,
+}
+
+impl FacetStats {
+    /// Create empty statistics.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            valid_count: 0,
+            invalid_count: 0,
+            date_suffix_count: 0,
+            invalid_date_suffix_count: 0,
+            depth_histogram: Vec::new(),
+        }
+    }
+
+    /// Record a facet.
+    pub fn record(&mut self, facet: &str) {
+        if validate(facet).is_err() {
+            self.invalid_count += 1;
+            return;
+        }
+        self.valid_count += 1;
+        if has_date_like_suffix(facet) {
+            if date_suffix(facet).is_some() {
+                self.date_suffix_count += 1;
+            } else {
+                self.invalid_date_suffix_count += 1;
+            }
+        }
+        let depth = segments(facet).count();
+        if self.depth_histogram.len() <= depth {
+            self.depth_histogram.resize(depth + 1, 0);
+        }
+        self.depth_histogram[depth] += 1;
+    }
+
+    /// The number of valid facets.
+    #[must_use]
+    pub const fn valid_count(&self) -> usize {
+        self.valid_count
+    }
+
+    /// The number of invalid facets.
+    #[must_use]
+    pub const fn invalid_count(&self) -> usize {
+        self.invalid_count
+    }
+
+    /// The number of valid facets with a valid date suffix.
+    #[must_use]
+    pub const fn date_suffix_count(&self) -> usize {
+        self.date_suffix_count
+    }
+
+    /// The number of valid facets with a date-like suffix that
+    /// doesn't encode a valid calendar date.
+    #[must_use]
+    pub const fn invalid_date_suffix_count(&self) -> usize {
+        self.invalid_date_suffix_count
+    }
+
+    /// The maximum number of segments of all valid facets.
+    ///
+    /// Returns `None` if no valid facets have been recorded.
+    #[must_use]
+    pub fn max_depth(&self) -> Option<usize> {
+        self.depth_histogram.len().checked_sub(1)
+    }
+
+    /// The number of valid facets, indexed by their number of segments.
+    #[must_use]
+    pub fn depth_histogram(&self) -> &[usize] {
+        &self.depth_histogram
+    }
+
+    /// Summarize the statistics in a single line.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "valid={valid} invalid={invalid} dated={dated} invalid_dates={invalid_dates} depths={depths:?}",
+            valid = self.valid_count,
+            invalid = self.invalid_count,
+            dated = self.date_suffix_count,
+            invalid_dates = self.invalid_date_suffix_count,
+            depths = self.depth_histogram,
+        )
+    }
+}
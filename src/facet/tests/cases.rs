@@ -0,0 +1,1904 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{borrow::Cow, collections::BTreeSet};
+
+use time::Date;
+
+use super::{Facet, FacetBuild as _, FacetError, FacetRef as _};
+
+#[test]
+fn try_split_into_prefix_and_date_like_suffix_should_accept_and_preserve_invalid_whitespace() {
+    let date = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    let facet = Facet::from_str("@20220625");
+    assert_eq!(
+        ("", Some(date)),
+        facet.try_split_into_prefix_and_parse_date_suffix().unwrap()
+    );
+    let facet = Facet::from_str("a \tb c\n @20220625");
+    assert_eq!(
+        ("a \tb c\n ", Some(date)),
+        facet.try_split_into_prefix_and_parse_date_suffix().unwrap()
+    );
+}
+
+#[test]
+fn try_split_into_prefix_and_date_like_suffix_should_accept_invalid_dates() {
+    let facet = Facet::from_str("@00000000");
+    assert_eq!(
+        ("", "@00000000"),
+        facet.try_split_into_prefix_and_date_like_suffix().unwrap()
+    );
+    assert_eq!(
+        ("", None),
+        facet.try_split_into_prefix_and_parse_date_suffix().unwrap()
+    );
+    let facet = Facet::from_str("abc@99999999");
+    assert_eq!(
+        ("abc", "@99999999"),
+        facet.try_split_into_prefix_and_date_like_suffix().unwrap()
+    );
+    assert_eq!(
+        ("abc", None),
+        facet.try_split_into_prefix_and_parse_date_suffix().unwrap()
+    );
+    let facet = Facet::from_str("abc @19700230");
+    assert_eq!(
+        ("abc ", "@19700230"),
+        facet.try_split_into_prefix_and_date_like_suffix().unwrap()
+    );
+    assert_eq!(
+        ("abc ", None),
+        facet.try_split_into_prefix_and_parse_date_suffix().unwrap()
+    );
+}
+
+#[test]
+fn has_date_like_suffix() {
+    assert!(super::has_date_like_suffix("@20220625"));
+    assert!(super::has_date_like_suffix("a@20220625"));
+    assert!(!super::has_date_like_suffix("a @20220625"));
+    assert!(!super::has_date_like_suffix("a-20220625"));
+    assert!(!super::has_date_like_suffix("a20220625"));
+}
+
+#[test]
+fn segments() {
+    assert_eq!(0, Facet::from_str("").segments().count());
+    assert_eq!(0, Facet::from_str("@20220625").segments().count());
+    assert_eq!(
+        vec!["genre"],
+        Facet::from_str("genre@20220625")
+            .segments()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["genre", "electronic", "house"],
+        Facet::from_str("genre/electronic/house")
+            .segments()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["a", "", "b"],
+        Facet::from_str("a//b@20220625")
+            .segments()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn count_by_top_segment() {
+    let facets = [
+        Facet::from_str("genre/electronic/house"),
+        Facet::from_str("genre/jazz"),
+        Facet::from_str("genre@20220625"),
+        Facet::from_str("mood/chill"),
+        Facet::from_str(""),
+        Facet::from_str("@20220625"),
+    ];
+    let counts = super::count_by_top_segment(&facets);
+    assert_eq!(
+        vec![("", 2), ("genre", 3), ("mood", 1)],
+        counts.into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn to_slug() {
+    assert_eq!("", Facet::from_str("").to_slug());
+    assert_eq!(
+        "genre-electronic-house",
+        Facet::from_str("genre/electronic/house").to_slug()
+    );
+    assert_eq!(
+        "genre-electronic-house-20220625",
+        Facet::from_str("Genre/Electronic/House@20220625").to_slug()
+    );
+    assert_eq!("20220625", Facet::from_str("@20220625").to_slug());
+}
+
+#[test]
+fn validate() {
+    assert_eq!(Ok(()), super::validate(""));
+    assert_eq!(Ok(()), super::validate("a facet@20220625"));
+    assert_eq!(
+        Err(FacetError::LeadingOrTrailingWhitespace),
+        super::validate(" facet")
+    );
+    assert_eq!(Err(FacetError::LeadingSlash), super::validate("/facet"));
+    assert_eq!(
+        Err(FacetError::InvalidDateLikeSuffix),
+        super::validate("facet @20220625")
+    );
+}
+
+#[test]
+fn absolute_and_relative_segments() {
+    assert!(!super::is_valid("/genre/house"));
+    assert!(super::is_valid_allowing_leading_slash("/genre/house"));
+    assert!(super::is_valid_allowing_leading_slash("genre/house"));
+    assert!(!super::is_valid_allowing_leading_slash("//genre/house"));
+    assert!(!super::is_valid_allowing_leading_slash("/ genre/house"));
+    assert!(super::is_absolute("/genre/house"));
+    assert!(!super::is_absolute("genre/house"));
+    assert_eq!(
+        vec!["", "genre", "house"],
+        super::segments("/genre/house@20220625").collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["genre", "house"],
+        super::segments("genre/house@20220625").collect::<Vec<_>>()
+    );
+    assert_eq!(vec![""], super::segments("/").collect::<Vec<_>>());
+    assert_eq!(vec![""], super::segments("/@20220625").collect::<Vec<_>>());
+}
+
+#[test]
+fn topics() {
+    let facets = [
+        Facet::from_str("played@20220625"),
+        Facet::from_str("wishlist"),
+        Facet::from_str("played@20220626"),
+        Facet::from_str("wishlist@20220625"),
+        Facet::from_str("@20220625"),
+        Facet::from_str("genre/house"),
+    ];
+    assert_eq!(
+        vec!["", "genre/house", "played", "wishlist"],
+        super::topics(&facets)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn starts_with_path_prefix() {
+    let facet = Facet::from_str("genre/electronic/house@20220625");
+    assert!(facet.starts_with_path_prefix(""));
+    assert!(facet.starts_with_path_prefix("genre"));
+    assert!(facet.starts_with_path_prefix("genre/electronic"));
+    assert!(facet.starts_with_path_prefix("genre/electronic/house"));
+    assert!(!facet.starts_with_path_prefix("genre/electronic/house/deep"));
+    assert!(!facet.starts_with_path_prefix("gen"));
+    assert!(!facet.starts_with_path_prefix("genre/elec"));
+    assert!(!Facet::from_str("genrefoo").starts_with_path_prefix("genre"));
+}
+
+#[test]
+fn prefix_matcher_longest_match() {
+    let matcher = super::PrefixMatcher::new(["genre", "genre/electronic", "mood/chill"]);
+    assert_eq!(
+        Some("genre/electronic"),
+        matcher.longest_match("genre/electronic/house")
+    );
+    assert_eq!(
+        Some("genre/electronic"),
+        matcher.longest_match("genre/electronic")
+    );
+    assert_eq!(Some("genre"), matcher.longest_match("genre/jazz@20220625"));
+    assert_eq!(
+        Some("mood/chill"),
+        matcher.longest_match("mood/chill/evening")
+    );
+    assert_eq!(None, matcher.longest_match("mood"));
+    assert_eq!(None, matcher.longest_match("genrefoo"));
+    assert_eq!(None, matcher.longest_match(""));
+    assert_eq!(
+        Some(""),
+        super::PrefixMatcher::new([""]).longest_match("mood")
+    );
+    assert_eq!(None, super::PrefixMatcher::default().longest_match("mood"));
+}
+
+#[test]
+fn is_reserved() {
+    let reserved_prefixes = ["@sys", "internal/cache"];
+    assert!(Facet::from_str("@sys").is_reserved(&reserved_prefixes));
+    assert!(Facet::from_str("@sys/foo").is_reserved(&reserved_prefixes));
+    assert!(Facet::from_str("internal/cache/foo").is_reserved(&reserved_prefixes));
+    assert!(!Facet::from_str("@system/foo").is_reserved(&reserved_prefixes));
+    assert!(!Facet::from_str("internal/foo").is_reserved(&reserved_prefixes));
+    assert!(!Facet::from_str("foo/@sys").is_reserved(&reserved_prefixes));
+    assert!(!Facet::from_str("@sys/foo").is_reserved(&[]));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn json_schema_pattern_matches_valid_facets() {
+    let regex = regex::Regex::new(super::VALID_FACET_REGEX_STR).unwrap();
+    for facet in [
+        "",
+        "a",
+        "genre/house",
+        "a facet@20220625",
+        " a",
+        "a ",
+        "a\n",
+        "/a",
+        "//a",
+        "a/",
+    ] {
+        assert_eq!(super::is_valid(facet), regex.is_match(facet), "{facet:?}");
+    }
+}
+
+#[test]
+fn detect_separator_style() {
+    use super::SeparatorStyle;
+    assert_eq!(
+        SeparatorStyle::None,
+        Facet::from_str("genre@20220625").detect_separator_style()
+    );
+    assert_eq!(
+        SeparatorStyle::Slash,
+        Facet::from_str("genre/electronic/house").detect_separator_style()
+    );
+    assert_eq!(
+        SeparatorStyle::Backslash,
+        Facet::from_str(r"genre\electronic\house").detect_separator_style()
+    );
+    assert_eq!(
+        SeparatorStyle::Dot,
+        Facet::from_str("genre.electronic.house").detect_separator_style()
+    );
+    assert_eq!(
+        SeparatorStyle::Mixed,
+        Facet::from_str(r"genre/electronic\house").detect_separator_style()
+    );
+    assert_eq!(
+        SeparatorStyle::Mixed,
+        Facet::from_str("genre/electronic.house@20220625").detect_separator_style()
+    );
+}
+
+#[test]
+fn date_suffix() {
+    assert_eq!(
+        Some(Date::from_calendar_date(2022, time::Month::June, 25).unwrap()),
+        Facet::from_str("played@20220625").date_suffix()
+    );
+    assert_eq!(None, Facet::from_str("played@19700230").date_suffix());
+    assert_eq!(None, Facet::from_str("played").date_suffix());
+    assert_eq!(None, Facet::from_str("played @20220625").date_suffix());
+}
+
+#[test]
+fn collect_dates_for_prefix() {
+    let facets = [
+        Facet::from_str("played@20220626"),
+        Facet::from_str("played@20220625"),
+        Facet::from_str("wishlist@20220624"),
+        Facet::from_str("played@20220626"),
+        Facet::from_str("played@19700230"),
+        Facet::from_str("played"),
+    ];
+    assert_eq!(
+        vec![
+            Date::from_calendar_date(2022, time::Month::June, 25).unwrap(),
+            Date::from_calendar_date(2022, time::Month::June, 26).unwrap(),
+        ],
+        super::collect_dates_for_prefix(&facets, "played")
+    );
+    assert!(super::collect_dates_for_prefix(&facets, "genre").is_empty());
+}
+
+#[test]
+fn date_span_for_prefix() {
+    let facets = [
+        Facet::from_str("played@20220626"),
+        Facet::from_str("played@20220624"),
+        Facet::from_str("wishlist@20220601"),
+        Facet::from_str("played@20220628"),
+        Facet::from_str("played@19700230"),
+        Facet::from_str("played"),
+    ];
+    let date = |day| Date::from_calendar_date(2022, time::Month::June, day).unwrap();
+    assert_eq!(
+        Some((date(24), date(28))),
+        super::date_span_for_prefix(&facets, "played")
+    );
+    assert_eq!(
+        Some((date(1), date(1))),
+        super::date_span_for_prefix(&facets, "wishlist")
+    );
+    assert_eq!(None, super::date_span_for_prefix(&facets, "genre"));
+}
+
+#[test]
+fn missing_dates_for_prefix() {
+    let facets = [
+        Facet::from_str("played@20220626"),
+        Facet::from_str("played@20220624"),
+        Facet::from_str("wishlist@20220625"),
+        Facet::from_str("played@20220628"),
+    ];
+    let date = |day| Date::from_calendar_date(2022, time::Month::June, day).unwrap();
+    assert_eq!(
+        vec![date(23), date(25), date(27)],
+        super::missing_dates_for_prefix(&facets, "played", date(23), date(28))
+    );
+    assert_eq!(
+        vec![date(24), date(26)],
+        super::missing_dates_for_prefix(&facets, "wishlist", date(24), date(26))
+    );
+    assert!(super::missing_dates_for_prefix(&facets, "played", date(28), date(23)).is_empty());
+    assert_eq!(
+        vec![Date::MAX],
+        super::missing_dates_for_prefix(&facets, "played", Date::MAX, Date::MAX)
+    );
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn eq_nfc() {
+    let composed = "genre/caf\u{e9}";
+    let decomposed = "genre/cafe\u{301}";
+    assert_ne!(composed, decomposed);
+    assert!(super::eq_nfc(composed, decomposed));
+    assert!(!super::eq_nfc(composed, "genre/cafe"));
+    assert!(matches!(
+        super::normalize_nfc(composed),
+        std::borrow::Cow::Borrowed(_)
+    ));
+    assert_eq!(composed, super::normalize_nfc(decomposed));
+}
+
+#[test]
+fn date_suffix_formatted() {
+    let format = time::macros::format_description!("[year]-[month]-[day]");
+    assert_eq!(
+        Some("2022-06-25".to_owned()),
+        Facet::from_str("played@20220625")
+            .date_suffix_formatted(format)
+            .transpose()
+            .unwrap()
+    );
+    assert!(Facet::from_str("played")
+        .date_suffix_formatted(format)
+        .is_none());
+    assert!(Facet::from_str("played@19700230")
+        .date_suffix_formatted(format)
+        .is_none());
+}
+
+#[test]
+fn date_suffix_cache() {
+    let mut cache = super::DateSuffixCache::new(2);
+    let date = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    assert!(cache.is_empty());
+    assert_eq!(Some(date), cache.get_or_parse("played@20220625"));
+    assert_eq!(None, cache.get_or_parse("played"));
+    assert_eq!(2, cache.len());
+    // Access the first entry again to evict the second entry next
+    assert_eq!(Some(date), cache.get_or_parse("played@20220625"));
+    assert_eq!(None, cache.get_or_parse("played@19700230"));
+    assert_eq!(2, cache.len());
+    assert_eq!(Some(date), cache.get_or_parse("played@20220625"));
+    assert_eq!(2, cache.len());
+    assert!(!cache.contains("played"));
+    assert!(cache.contains("played@19700230"));
+}
+
+#[test]
+fn date_suffix_cache_evicts_least_recently_used_after_repeated_hits() {
+    let mut cache = super::DateSuffixCache::new(3);
+    for facet in ["a", "b", "c"] {
+        cache.get_or_parse(facet);
+    }
+    for _ in 0..100 {
+        cache.get_or_parse("c");
+        cache.get_or_parse("a");
+    }
+    cache.get_or_parse("d");
+    assert_eq!(3, cache.len());
+    assert!(!cache.contains("b"));
+    cache.get_or_parse("e");
+    assert!(!cache.contains("c"));
+    assert!(cache.contains("a"));
+    assert!(cache.contains("d"));
+    assert!(cache.contains("e"));
+}
+
+#[test]
+fn date_suffix_cache_with_zero_capacity() {
+    let mut cache = super::DateSuffixCache::new(0);
+    assert_eq!(1, cache.capacity());
+    assert_eq!(None, cache.get_or_parse("a"));
+    assert_eq!(None, cache.get_or_parse("b"));
+    assert_eq!(1, cache.len());
+    assert!(cache.contains("b"));
+}
+
+#[test]
+fn from_prefix_with_date_suffix_at_date_range_extremes() {
+    let facet = Facet::from_prefix_with_date_suffix("max", Date::MAX).unwrap();
+    assert_eq!("max@99991231", &*facet);
+    assert_eq!(Some(Date::MAX), facet.date_suffix());
+    let date = Date::from_calendar_date(0, time::Month::January, 1).unwrap();
+    let facet = Facet::from_prefix_with_date_suffix("zero", date).unwrap();
+    assert_eq!("zero@00000101", &*facet);
+    assert_eq!(Some(date), facet.date_suffix());
+    let date = Date::from_calendar_date(999, time::Month::January, 1).unwrap();
+    let facet = Facet::from_prefix_with_date_suffix("", date).unwrap();
+    assert_eq!("@09990101", &*facet);
+    // Negative years cannot be encoded
+    assert!(Facet::from_prefix_with_date_suffix("min", Date::MIN).is_err());
+    let date = Date::from_calendar_date(-1, time::Month::December, 31).unwrap();
+    assert!(Facet::from_prefix_with_date_suffix("", date).is_err());
+    assert!(super::StdFacet::from_prefix_args_with_date_suffix(format_args!("min"), date).is_err());
+}
+
+#[test]
+fn strip_path_prefix() {
+    let facet = Facet::from_str("genre/electronic/house");
+    assert_eq!(Some("electronic/house"), facet.strip_path_prefix("genre"));
+    assert_eq!(Some("house"), facet.strip_path_prefix("genre/electronic"));
+    assert_eq!(Some(""), facet.strip_path_prefix("genre/electronic/house"));
+    assert_eq!(Some("genre/electronic/house"), facet.strip_path_prefix(""));
+    assert_eq!(None, facet.strip_path_prefix("genre/elec"));
+    assert_eq!(None, facet.strip_path_prefix("genre/electronic/house/deep"));
+    assert_eq!(None, super::strip_path_prefix("genrefoo", "genre"));
+    assert_eq!(
+        Some("house@20220625"),
+        super::strip_path_prefix("genre/house@20220625", "genre")
+    );
+    assert_eq!(
+        Some("@20220625"),
+        super::strip_path_prefix("genre@20220625", "genre")
+    );
+    assert_eq!(None, super::strip_path_prefix("genre@2022", "genre"));
+    assert_eq!(
+        Some("house"),
+        super::strip_path_prefix("genre/house", "genre@20220625")
+    );
+    assert_eq!(Some("/genre"), super::strip_path_prefix("/genre", ""));
+    assert_eq!(Some("genre"), super::strip_path_prefix("/genre", "/"));
+}
+
+#[test]
+fn strip_path_prefix_is_consistent_with_starts_with_path_prefix() {
+    let facets = [
+        "",
+        "@20220625",
+        "genre",
+        "genre@20220625",
+        "genres",
+        "genre/house",
+        "genre/house@20220625",
+        "genre//house",
+        "/genre/house",
+    ];
+    let prefixes = [
+        "",
+        "@20220625",
+        "genre",
+        "genre@20220625",
+        "genre@2022",
+        "genre/",
+        "genre/house",
+        "genre/house@20220625",
+        "genre/house/deep",
+        "/",
+        "/genre",
+    ];
+    for facet in facets {
+        for prefix in prefixes {
+            assert_eq!(
+                super::starts_with_path_prefix(facet, prefix),
+                super::strip_path_prefix(facet, prefix).is_some(),
+                "{facet:?} {prefix:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn explain_date_suffix() {
+    use super::DateSuffixReport;
+    assert_eq!(
+        DateSuffixReport::NoSuffix,
+        Facet::from_str("abc").explain_date_suffix()
+    );
+    assert_eq!(
+        DateSuffixReport::NoSuffix,
+        Facet::from_str("abc @20220625").explain_date_suffix()
+    );
+    assert_eq!(
+        DateSuffixReport::ValidDate(Date::from_calendar_date(2022, time::Month::June, 25).unwrap()),
+        Facet::from_str("abc@20220625").explain_date_suffix()
+    );
+    let facet = Facet::from_str("abc@19700230");
+    let DateSuffixReport::InvalidDate { raw, error } = facet.explain_date_suffix() else {
+        panic!("unexpected report");
+    };
+    assert_eq!("@19700230", raw);
+    assert!(matches!(error, time::error::Parse::TryFromParsed(_)));
+}
+
+#[test]
+fn eq_case_insensitive_path_exact_date() {
+    use super::eq_case_insensitive_path_exact_date;
+    assert!(eq_case_insensitive_path_exact_date(
+        "genre/house@20220101",
+        "genre/house@20220101"
+    ));
+    assert!(eq_case_insensitive_path_exact_date(
+        "Genre/House@20220101",
+        "genre/house@20220101"
+    ));
+    assert!(eq_case_insensitive_path_exact_date(
+        "Genre/House",
+        "genre/house"
+    ));
+    assert!(!eq_case_insensitive_path_exact_date(
+        "Genre/House@20220101",
+        "genre/house@20220102"
+    ));
+    assert!(!eq_case_insensitive_path_exact_date(
+        "genre/house@20220101",
+        "genre/house"
+    ));
+    assert!(!eq_case_insensitive_path_exact_date(
+        "genre/house@20220101",
+        "genre/techno@20220101"
+    ));
+}
+
+pub(super) struct FixedClock(pub(super) Date);
+
+impl super::Clock for FixedClock {
+    fn today(&self) -> Date {
+        self.0
+    }
+}
+
+#[test]
+fn from_prefix_with_today() {
+    let clock = FixedClock(Date::from_calendar_date(2022, time::Month::June, 25).unwrap());
+    assert_eq!(
+        Facet::from_str("played@20220625"),
+        Facet::from_prefix_with_today("played", &clock).unwrap()
+    );
+    assert_eq!(
+        Facet::from_str("@20220625"),
+        Facet::from_prefix_with_today("", &clock).unwrap()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn from_prefix_with_today_system_clock() {
+    let facet = Facet::from_prefix_with_today("played", &super::SystemClock).unwrap();
+    assert!(facet.date_suffix().is_some());
+}
+
+#[test]
+fn is_valid_charset() {
+    const STRICT: super::CharSet = super::CharSet::new()
+        .with_ascii_range(b'a', b'z')
+        .with_ascii_range(b'0', b'9')
+        .with_ascii_chars(b"/_-");
+    assert!(Facet::from_str("").is_valid_charset(&STRICT));
+    assert!(Facet::from_str("genre/deep-house_2").is_valid_charset(&STRICT));
+    assert!(Facet::from_str("genre/house@20220625").is_valid_charset(&STRICT));
+    assert!(!Facet::from_str("genre/House").is_valid_charset(&STRICT));
+    assert!(!Facet::from_str("genre/deep house").is_valid_charset(&STRICT));
+    assert!(!Facet::from_str("genre@house").is_valid_charset(&STRICT));
+    assert!(!Facet::from_str("genre/caf\u{e9}").is_valid_charset(&STRICT));
+    assert!(Facet::from_str("genre/caf\u{e9}").is_valid_charset(&STRICT.with_non_ascii(true)));
+    assert!(!Facet::from_str("genre/Caf\u{e9}").is_valid_charset(&STRICT.with_non_ascii(true)));
+}
+
+#[test]
+fn is_valid_parity_with_trim() {
+    fn is_valid_trim(facet: &str) -> bool {
+        facet.trim() == facet && facet.as_bytes().first() != Some(&b'/')
+    }
+    for facet in [
+        "",
+        " ",
+        "/",
+        "a",
+        "\u{a0}",
+        "\u{e9}",
+        "a/",
+        "/a",
+        " a",
+        "a ",
+        "a\n",
+        "\ta",
+        "a b",
+        "a\u{2003}",
+        "\u{2003}a",
+        "genre/house@20220625",
+        "genre/house @20220625",
+    ] {
+        assert_eq!(is_valid_trim(facet), super::is_valid(facet), "{facet:?}");
+    }
+}
+
+#[test]
+fn date_suffix_components() {
+    assert_eq!(
+        Some((2022, 6, 25)),
+        Facet::from_str("played@20220625").date_suffix_components()
+    );
+    assert_eq!(
+        Some((2022, 6, 31)),
+        Facet::from_str("played@20220631").date_suffix_components()
+    );
+    assert_eq!(
+        Some((0, 0, 0)),
+        Facet::from_str("@00000000").date_suffix_components()
+    );
+    assert_eq!(None, Facet::from_str("played").date_suffix_components());
+    assert_eq!(
+        None,
+        Facet::from_str("played @20220625").date_suffix_components()
+    );
+}
+
+#[test]
+fn render_path() {
+    let facet = Facet::from_str("genre/electronic/house@20220625");
+    assert_eq!("genre::electronic::house@20220625", facet.render_path("::"));
+    assert!(matches!(facet.render_path("/"), Cow::Borrowed(_)));
+    assert!(matches!(
+        Facet::from_str("genre@20220625").render_path("::"),
+        Cow::Borrowed("genre@20220625")
+    ));
+    assert_eq!("a..b", Facet::from_str("a//b").render_path("."));
+}
+
+#[test]
+fn segment_spans() {
+    let facet = Facet::from_str("genre/electronic/house@20220625");
+    let spans = facet.segment_spans().collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (0..5, "genre"),
+            (6..16, "electronic"),
+            (17..22, "house"),
+            (22..31, "@20220625"),
+        ],
+        spans
+    );
+    for (range, text) in spans {
+        assert_eq!(&facet[range], text);
+    }
+    assert_eq!(
+        vec![(0..0, ""), (1..2, "a"), (3..3, ""), (4..5, "b")],
+        super::segment_spans("/a//b").collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![(0..9, "@20220625")],
+        super::segment_spans("@20220625").collect::<Vec<_>>()
+    );
+    assert_eq!(0, super::segment_spans("").count());
+}
+
+#[test]
+fn ordinal_date_suffix() {
+    let date = Date::from_ordinal_date(2022, 176).unwrap();
+    let facet = Facet::from_prefix_with_ordinal_date_suffix("sampled", date).unwrap();
+    assert_eq!("sampled@2022176", &*facet);
+    assert_eq!(
+        Some(("sampled", date)),
+        facet.try_split_into_prefix_and_ordinal_date()
+    );
+    // Calendar date suffixes are distinguished by their length
+    assert_eq!(
+        None,
+        Facet::from_str("played@20220625").try_split_into_prefix_and_ordinal_date()
+    );
+    assert_eq!(None, Facet::from_str("sampled@2022176").date_suffix());
+    assert_eq!(
+        None,
+        Facet::from_str("sampled @2022176").try_split_into_prefix_and_ordinal_date()
+    );
+}
+
+#[test]
+fn ordinal_date_suffix_boundaries() {
+    let leap_day = Date::from_ordinal_date(2024, 366).unwrap();
+    assert_eq!(
+        Some(("", leap_day)),
+        Facet::from_str("@2024366").try_split_into_prefix_and_ordinal_date()
+    );
+    assert_eq!(
+        "@2024366",
+        &*Facet::from_prefix_with_ordinal_date_suffix("", leap_day).unwrap()
+    );
+    assert_eq!(
+        None,
+        Facet::from_str("@2023366").try_split_into_prefix_and_ordinal_date()
+    );
+    assert_eq!(
+        None,
+        Facet::from_str("@2024367").try_split_into_prefix_and_ordinal_date()
+    );
+    assert_eq!(
+        None,
+        Facet::from_str("@2024000").try_split_into_prefix_and_ordinal_date()
+    );
+}
+
+#[test]
+fn cmp_hierarchical() {
+    let mut facets = vec![
+        "genre/house-music",
+        "genre/house@20220625",
+        "genre/house/deep",
+        "genre",
+        "genre/house",
+        "genre-x",
+    ];
+    facets.sort_unstable_by(|lhs, rhs| super::cmp_hierarchical(lhs, rhs));
+    assert_eq!(
+        vec![
+            "genre",
+            "genre/house",
+            "genre/house/deep",
+            "genre/house-music",
+            "genre/house@20220625",
+            "genre-x",
+        ],
+        facets
+    );
+    // The lexical ordering differs
+    assert!("genre/house-music" < "genre/house/deep");
+    assert!("genre-x" < "genre/house");
+    assert_eq!(
+        std::cmp::Ordering::Equal,
+        super::cmp_hierarchical("genre/house", "genre/house")
+    );
+}
+
+#[test]
+fn build_tree() {
+    let facets = [
+        Facet::from_str("genre/electronic/house@20220625"),
+        Facet::from_str("genre/electronic"),
+        Facet::from_str("genre/electronic/house"),
+        Facet::from_str("mood"),
+        Facet::from_str("@20220625"),
+    ];
+    let root = super::build_tree(&facets);
+    assert_eq!("", root.segment());
+    assert_eq!(vec![&facets[4]], root.facets());
+    assert_eq!(
+        vec!["genre", "mood"],
+        root.children()
+            .map(super::FacetTree::segment)
+            .collect::<Vec<_>>()
+    );
+    let genre = root.child("genre").unwrap();
+    assert!(genre.facets().is_empty());
+    let electronic = genre.child("electronic").unwrap();
+    assert_eq!(vec![&facets[1]], electronic.facets());
+    let house = electronic.child("house").unwrap();
+    assert_eq!(vec![&facets[0], &facets[2]], house.facets());
+    assert_eq!(0, house.children().count());
+    assert_eq!(vec![&facets[3]], root.child("mood").unwrap().facets());
+    assert!(root.child("electronic").is_none());
+}
+
+#[test]
+fn separator_count() {
+    assert_eq!(0, Facet::from_str("").separator_count());
+    assert_eq!(0, Facet::from_str("genre@20220625").separator_count());
+    assert_eq!(1, Facet::from_str("genre/house").separator_count());
+    assert_eq!(2, Facet::from_str("genre//house").separator_count());
+    for facet in [
+        "genre",
+        "genre/house",
+        "genre//house",
+        "genre/house@20220625",
+    ] {
+        let facet = Facet::from_str(facet);
+        assert_eq!(facet.separator_count() + 1, facet.segments().count());
+    }
+}
+
+#[test]
+fn cmp_by_date_desc() {
+    let mut facets = vec![
+        "genre/house",
+        "played@20220624",
+        "wishlist@20220625",
+        "played@19700230",
+        "played@20220625",
+        "",
+        "@20220626",
+    ];
+    facets.sort_unstable_by(|lhs, rhs| super::cmp_by_date_desc(lhs, rhs));
+    assert_eq!(
+        vec![
+            "@20220626",
+            "played@20220625",
+            "wishlist@20220625",
+            "played@20220624",
+            "",
+            "genre/house",
+            "played@19700230",
+        ],
+        facets
+    );
+}
+
+#[test]
+fn is_valid_non_dateonly() {
+    assert!(!Facet::from_str("@20220625").is_valid_non_dateonly());
+    assert!(Facet::from_str("@20220625").is_valid());
+    assert!(Facet::from_str("event@20220625").is_valid_non_dateonly());
+    assert!(Facet::from_str("event").is_valid_non_dateonly());
+    assert!(Facet::from_str("").is_valid_non_dateonly());
+    assert!(Facet::from_str("@2022062").is_valid_non_dateonly());
+    assert!(!Facet::from_str(" event@20220625").is_valid_non_dateonly());
+}
+
+#[test]
+fn ancestors() {
+    assert_eq!(
+        vec!["genre", "genre/electronic"],
+        Facet::from_str("genre/electronic/house")
+            .ancestors()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["genre"],
+        Facet::from_str("genre/house@20220625")
+            .ancestors()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["/genre"],
+        super::ancestors("/genre/house").collect::<Vec<_>>()
+    );
+    assert_eq!(0, Facet::from_str("genre").ancestors().count());
+    assert_eq!(0, Facet::from_str("").ancestors().count());
+}
+
+#[test]
+fn sanitize_for_filename() {
+    assert_eq!(
+        "genre_house@20220625",
+        Facet::from_str("genre/house@20220625").sanitize_for_filename()
+    );
+    assert_eq!(
+        "what_ a _mix_ _1_2_",
+        Facet::from_str("what? a *mix* <1|2>").sanitize_for_filename()
+    );
+    assert_eq!("C__dir_file", super::sanitize_for_filename("C:\\dir\\file"));
+    assert_eq!("tab_", super::sanitize_for_filename("tab\t"));
+    // Trailing dots and spaces
+    assert_eq!("etc", Facet::from_str("etc...").sanitize_for_filename());
+    assert_eq!("etc", super::sanitize_for_filename("etc. . "));
+    assert_eq!("_", Facet::from_str("..").sanitize_for_filename());
+    assert_eq!("_", Facet::from_str("").sanitize_for_filename());
+    // Reserved device names
+    assert_eq!("_CON", Facet::from_str("CON").sanitize_for_filename());
+    assert_eq!(
+        "_nul.txt",
+        Facet::from_str("nul.txt").sanitize_for_filename()
+    );
+    assert_eq!("_Com1", Facet::from_str("Com1.").sanitize_for_filename());
+    assert_eq!(
+        "CONSOLE",
+        Facet::from_str("CONSOLE").sanitize_for_filename()
+    );
+    assert_eq!("COM10", Facet::from_str("COM10").sanitize_for_filename());
+}
+
+#[test]
+fn intersection_sorted() {
+    let lhs = [
+        Facet::from_str("genre/house"),
+        Facet::from_str("genre/house"),
+        Facet::from_str("genre/techno"),
+        Facet::from_str("mood/chill"),
+        Facet::from_str("played@20220625"),
+    ];
+    let rhs = [
+        Facet::from_str("genre/house"),
+        Facet::from_str("mood/chill"),
+        Facet::from_str("mood/chill"),
+        Facet::from_str("played@20220626"),
+    ];
+    assert_eq!(
+        vec![&lhs[0], &lhs[3]],
+        super::intersection_sorted(&lhs, &rhs)
+    );
+    assert_eq!(
+        vec![&lhs[0], &lhs[1]],
+        super::intersection_sorted(&lhs[..2], &lhs[..2])
+    );
+    assert!(super::intersection_sorted(&lhs[..3], &rhs[1..]).is_empty());
+    assert!(super::intersection_sorted(&lhs, &[]).is_empty());
+}
+
+#[test]
+fn describe() {
+    assert_eq!(
+        "segments=[genre, house] date=2022-06-25",
+        Facet::from_str("genre/house@20220625").describe()
+    );
+    assert_eq!(
+        "segments=[played] date=INVALID(@19700230)",
+        Facet::from_str("played@19700230").describe()
+    );
+    assert_eq!("segments=[event]", Facet::from_str("event").describe());
+    assert_eq!(
+        "segments=[] date=2022-06-25",
+        Facet::from_str("@20220625").describe()
+    );
+    assert_eq!("segments=[]", Facet::from_str("").describe());
+}
+
+#[test]
+fn dedup_case_insensitive_keep_first() {
+    let facets = [
+        "Genre/House",
+        "mood/chill",
+        "genre/house",
+        "GENRE/HOUSE",
+        "Mood/Chill@20220625",
+        "genre/Caf\u{c9}",
+        "genre/caf\u{e9}",
+    ]
+    .map(Facet::from_str);
+    assert_eq!(
+        vec![
+            Facet::from_str("Genre/House"),
+            Facet::from_str("mood/chill"),
+            Facet::from_str("Mood/Chill@20220625"),
+            Facet::from_str("genre/Caf\u{c9}"),
+        ],
+        super::dedup_case_insensitive_keep_first(facets)
+    );
+}
+
+#[test]
+fn try_split_suffix() {
+    use super::try_split_suffix;
+
+    assert_eq!(Some(("abc", "@12")), try_split_suffix("abc@12", 3, '@'));
+    assert_eq!(Some(("", "@12")), try_split_suffix("@12", 3, '@'));
+    assert_eq!(None, try_split_suffix("abc@12", 0, '@'));
+    assert_eq!(None, try_split_suffix("@12", 4, '@'));
+    assert_eq!(None, try_split_suffix("abc@12", 2, '@'));
+    // Not a char boundary
+    assert_eq!(None, try_split_suffix("ab\u{e9}12", 3, '@'));
+}
+
+#[test]
+fn try_split_suffix_fuzz() {
+    use super::try_split_suffix;
+
+    const CHARS: &[char] = &['a', '@', '1', ' ', '/', '\u{e9}', '\u{1f600}'];
+
+    // Deterministic pseudo-random numbers (xorshift)
+    let mut state = 0x2545_f491_usize;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..10_000 {
+        let len = next() % 16;
+        let facet = (0..len)
+            .map(|_| CHARS[next() % CHARS.len()])
+            .collect::<String>();
+        let suffix_len = next() % 24;
+        let sep = CHARS[next() % CHARS.len()];
+        if let Some((prefix, suffix)) = try_split_suffix(&facet, suffix_len, sep) {
+            assert_eq!(facet, format!("{prefix}{suffix}"));
+            assert_eq!(suffix_len, suffix.len());
+            assert!(suffix.starts_with(sep));
+        } else {
+            assert!(
+                suffix_len > facet.len()
+                    || !facet.is_char_boundary(facet.len() - suffix_len)
+                    || !facet[facet.len() - suffix_len..].starts_with(sep)
+            );
+        }
+    }
+}
+
+#[test]
+fn csv_field() {
+    use super::{from_csv_field, FacetFromCsvError};
+
+    let facet = Facet::from_str("genre/house@20220625");
+    assert!(matches!(
+        facet.to_csv_field(),
+        Cow::Borrowed("genre/house@20220625")
+    ));
+    assert_eq!(
+        Ok(Cow::Borrowed(&*facet)),
+        from_csv_field(&facet.to_csv_field())
+    );
+
+    let facet = Facet::from_str("artist/earth, wind & fire");
+    assert_eq!("\"artist/earth, wind & fire\"", facet.to_csv_field());
+    assert_eq!(
+        Ok(Cow::Borrowed(&*facet)),
+        from_csv_field(&facet.to_csv_field())
+    );
+
+    let facet = Facet::from_str("title/say \"hello\"");
+    assert_eq!("\"title/say \"\"hello\"\"\"", facet.to_csv_field());
+    assert_eq!(&*facet, from_csv_field(&facet.to_csv_field()).unwrap());
+
+    assert_eq!(
+        Err(FacetFromCsvError::InvalidQuoting),
+        from_csv_field("\"genre/house")
+    );
+    assert_eq!(
+        Err(FacetFromCsvError::InvalidQuoting),
+        from_csv_field("\"say \"hello\"\"")
+    );
+    assert_eq!(
+        Err(FacetFromCsvError::InvalidQuoting),
+        from_csv_field("genre,house")
+    );
+    assert_eq!(
+        Err(FacetFromCsvError::InvalidFacet(FacetError::LeadingSlash)),
+        from_csv_field("\"/genre,house\"")
+    );
+}
+
+#[test]
+fn date_suffix_iso_week() {
+    assert_eq!(
+        Some((2022, 25)),
+        Facet::from_str("played@20220625").date_suffix_iso_week()
+    );
+    // Saturday, January 1 2022 belongs to the last week of 2021
+    assert_eq!(
+        Some((2021, 52)),
+        Facet::from_str("played@20220101").date_suffix_iso_week()
+    );
+    // Monday, December 29 2025 belongs to the first week of 2026
+    assert_eq!(
+        Some((2026, 1)),
+        Facet::from_str("played@20251229").date_suffix_iso_week()
+    );
+    assert_eq!(
+        None,
+        Facet::from_str("played@19700230").date_suffix_iso_week()
+    );
+    assert_eq!(None, Facet::from_str("played").date_suffix_iso_week());
+}
+
+#[test]
+fn facet_stats() {
+    let mut stats = super::FacetStats::new();
+    assert_eq!(None, stats.max_depth());
+    for facet in [
+        "genre/electronic/house",
+        "genre/techno@20220625",
+        "played@20220625",
+        "played@19700230",
+        "@20220625",
+        "",
+        "/genre",
+        " mood",
+        "played @20220625",
+    ] {
+        stats.record(facet);
+    }
+    assert_eq!(6, stats.valid_count());
+    assert_eq!(3, stats.invalid_count());
+    assert_eq!(3, stats.date_suffix_count());
+    assert_eq!(1, stats.invalid_date_suffix_count());
+    assert_eq!(Some(3), stats.max_depth());
+    assert_eq!(&[2, 2, 1, 1], stats.depth_histogram());
+    assert_eq!(
+        "valid=6 invalid=3 dated=3 invalid_dates=1 depths=[2, 2, 1, 1]",
+        stats.summary()
+    );
+}
+
+#[test]
+fn is_more_specific_than() {
+    use super::is_more_specific_than;
+
+    // Depth
+    assert!(is_more_specific_than(
+        "genre/electronic/house@20220625",
+        "genre/electronic"
+    ));
+    assert!(is_more_specific_than("genre/electronic", "genre@20220625"));
+    assert!(!is_more_specific_than("genre", "genre/electronic"));
+    // Date presence at equal depth
+    assert!(is_more_specific_than("genre@20220625", "genre"));
+    assert!(is_more_specific_than("mood@20220625", "genre"));
+    assert!(!is_more_specific_than("genre", "genre@20220625"));
+    assert!(!is_more_specific_than("genre@19700230", "genre"));
+    // Neither
+    assert!(!is_more_specific_than("genre@20220625", "genre@20220626"));
+    assert!(!is_more_specific_than("genre", "genre"));
+}
+
+#[test]
+fn expand_pattern() {
+    use super::{expand_pattern, matches_glob};
+
+    let universe = [
+        Facet::from_str("genre"),
+        Facet::from_str("genre/house"),
+        Facet::from_str("genre/techno@20220625"),
+        Facet::from_str("genre/electronic/house"),
+        Facet::from_str("mood/chill"),
+    ];
+    assert_eq!(
+        vec![&universe[1], &universe[2]],
+        expand_pattern("genre/*", &universe)
+    );
+    assert_eq!(
+        vec![&universe[0], &universe[1], &universe[2], &universe[3]],
+        expand_pattern("genre/**", &universe)
+    );
+    assert_eq!(
+        vec![&universe[1], &universe[3]],
+        expand_pattern("**/house", &universe)
+    );
+    assert_eq!(vec![&universe[3]], expand_pattern("*/*/house", &universe));
+    assert_eq!(vec![&universe[4]], expand_pattern("mood/chill", &universe));
+    assert!(expand_pattern("genre/*/techno", &universe).is_empty());
+    assert!(matches_glob("a/b/c", "**"));
+    assert!(matches_glob("a/b/c", "a/**/c"));
+    assert!(matches_glob("a/c", "a/**/c"));
+    assert!(!matches_glob("a/b/c", "a/*"));
+}
+
+#[test]
+fn segments_with() {
+    let facet = Facet::from_str("genre:electronic:house@20220625");
+    assert_eq!(
+        vec!["genre", "electronic", "house"],
+        facet.segments_with(':').collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["genre:electronic:house"],
+        facet.segments().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["genre/house", "deep"],
+        super::segments_with("genre/house.deep", '.').collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["", "genre", "house"],
+        super::segments_with("/genre:house", ':').collect::<Vec<_>>()
+    );
+    assert_eq!(0, Facet::from_str("@20220625").segments_with(':').count());
+}
+
+#[test]
+fn hierarchy_helpers_with_custom_separator() {
+    use std::cmp::Ordering;
+
+    let facet = Facet::from_str("genre:electronic:house@20220625");
+    assert_eq!(
+        vec!["genre", "genre:electronic"],
+        facet.ancestors_with(':').collect::<Vec<_>>()
+    );
+    assert_eq!(0, facet.ancestors().count());
+    assert_eq!(
+        vec![
+            (0..5, "genre"),
+            (6..16, "electronic"),
+            (17..22, "house"),
+            (22..31, "@20220625"),
+        ],
+        facet.segment_spans_with(':').collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![(0..0, ""), (1..6, "genre"), (7..12, "house")],
+        super::segment_spans_with("/genre:house", ':').collect::<Vec<_>>()
+    );
+    assert_eq!(2, facet.separator_count_with(':'));
+    assert_eq!(0, facet.separator_count());
+    assert!(facet.starts_with_path_prefix_with("genre:electronic", ':'));
+    assert!(facet.starts_with_path_prefix_with("genre@20220625", ':'));
+    assert!(!facet.starts_with_path_prefix_with("genre:elec", ':'));
+    assert!(!facet.starts_with_path_prefix("genre"));
+    assert_eq!(
+        Some("house@20220625"),
+        facet.strip_path_prefix_with("genre:electronic", ':')
+    );
+    assert_eq!(
+        Some("@20220625"),
+        facet.strip_path_prefix_with("genre:electronic:house", ':')
+    );
+    assert_eq!(None, facet.strip_path_prefix_with("genre:elec", ':'));
+    assert_eq!(
+        Ordering::Less,
+        super::cmp_hierarchical_with("genre:house", "genre:house:deep", ':')
+    );
+    assert_eq!(
+        Ordering::Less,
+        super::cmp_hierarchical_with("genre:house:deep", "genre:house-music", ':')
+    );
+    assert_eq!(
+        Ordering::Greater,
+        super::cmp_hierarchical("genre:house:deep", "genre:house-music")
+    );
+}
+
+#[test]
+#[should_panic(expected = "the separator of date-like suffixes cannot separate segments")]
+fn segments_with_date_suffix_separator() {
+    let _ = super::segments_with("genre@house", '@');
+}
+
+#[test]
+fn date_suffix_age() {
+    let today = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    assert_eq!(
+        Some(time::Duration::days(3)),
+        Facet::from_str("played@20220622").date_suffix_age(today)
+    );
+    assert_eq!(
+        Some(time::Duration::ZERO),
+        Facet::from_str("played@20220625").date_suffix_age(today)
+    );
+    assert_eq!(
+        Some(time::Duration::days(-7)),
+        Facet::from_str("planned@20220702").date_suffix_age(today)
+    );
+    assert_eq!(None, Facet::from_str("played").date_suffix_age(today));
+    assert_eq!(
+        None,
+        Facet::from_str("played@19700230").date_suffix_age(today)
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn validate_par() {
+    let inputs = (0..10_000)
+        .map(|i| match i % 4 {
+            0 => format!("genre/{i}"),
+            1 => format!("/genre/{i}"),
+            2 => format!("played {i}@20220625"),
+            _ => format!("played @{i:08}"),
+        })
+        .collect::<Vec<_>>();
+    let sequential = inputs
+        .iter()
+        .map(|input| super::validate(input))
+        .collect::<Vec<_>>();
+    assert_eq!(sequential, super::validate_par(&inputs));
+}
+
+#[test]
+fn classify() {
+    use super::FacetKind;
+
+    assert_eq!(FacetKind::Empty, Facet::from_str("").classify());
+    assert_eq!(FacetKind::DateOnly, Facet::from_str("@20220625").classify());
+    assert_eq!(
+        FacetKind::Dated { prefix_depth: 2 },
+        Facet::from_str("genre/house@20220625").classify()
+    );
+    assert_eq!(
+        FacetKind::Plain { depth: 1 },
+        Facet::from_str("genre").classify()
+    );
+    assert_eq!(
+        FacetKind::Plain { depth: 3 },
+        Facet::from_str("genre/electronic/house").classify()
+    );
+    assert_eq!(
+        FacetKind::InvalidDate,
+        Facet::from_str("played@19700230").classify()
+    );
+    assert_eq!(
+        FacetKind::InvalidDate,
+        Facet::from_str("@00000000").classify()
+    );
+    assert_eq!(
+        FacetKind::Plain { depth: 1 },
+        Facet::from_str("genre@abcdefgh").classify()
+    );
+    // Only the last date-like suffix is split off
+    assert_eq!(
+        FacetKind::Dated { prefix_depth: 1 },
+        Facet::from_str("event@20220101@20220202").classify()
+    );
+    for facet in [
+        "",
+        "@20220625",
+        "genre/house@20220625",
+        "genre/electronic/house",
+        "played@19700230",
+        "event@20220101@20220202",
+    ] {
+        let depth = super::segments(facet).count();
+        match super::classify(facet) {
+            FacetKind::Empty | FacetKind::DateOnly => assert_eq!(0, depth),
+            FacetKind::Dated { prefix_depth } => assert_eq!(depth, prefix_depth),
+            FacetKind::Plain { depth: plain_depth } => assert_eq!(depth, plain_depth),
+            FacetKind::InvalidDate => {}
+        }
+    }
+}
+
+#[test]
+fn nearest_ancestor_in() {
+    use super::nearest_ancestor_in;
+
+    let set = [
+        Facet::from_str("genre"),
+        Facet::from_str("genre/electronic"),
+        Facet::from_str("mood/chill"),
+    ];
+    assert_eq!(
+        Some(&set[1]),
+        nearest_ancestor_in("genre/electronic/house/deep", &set)
+    );
+    assert_eq!(
+        Some(&set[1]),
+        nearest_ancestor_in("genre/electronic/deep-house@20220625", &set)
+    );
+    assert_eq!(Some(&set[0]), nearest_ancestor_in("genre/electronic", &set));
+    assert_eq!(None, nearest_ancestor_in("genre", &set));
+    assert_eq!(None, nearest_ancestor_in("mood/happy", &set));
+}
+
+#[test]
+fn multiple_date_like_suffixes() {
+    use super::{has_multiple_date_like_suffixes, strip_all_date_like_suffixes};
+
+    assert!(has_multiple_date_like_suffixes("event@20220101@20220202"));
+    assert_eq!(
+        "event",
+        strip_all_date_like_suffixes("event@20220101@20220202")
+    );
+    assert!(has_multiple_date_like_suffixes(
+        "event@20220101/party@20220202"
+    ));
+    assert_eq!(
+        "event",
+        strip_all_date_like_suffixes("event@20220101/party@20220202")
+    );
+
+    assert!(!has_multiple_date_like_suffixes("event@20220101"));
+    assert_eq!("event", strip_all_date_like_suffixes("event@20220101"));
+    assert!(!has_multiple_date_like_suffixes("event@202201010@20220202"));
+    assert_eq!(
+        "event@202201010",
+        strip_all_date_like_suffixes("event@202201010@20220202")
+    );
+
+    assert!(!has_multiple_date_like_suffixes("event"));
+    assert_eq!("event", strip_all_date_like_suffixes("event"));
+    assert_eq!("", strip_all_date_like_suffixes("@20220101"));
+}
+
+#[test]
+fn to_anonymized() {
+    let anonymized = Facet::from_str("genre/house@20220625").to_anonymized();
+    let (path, date) = anonymized.split_once('@').unwrap();
+    assert_eq!("20220625", date);
+    let tokens = path.split('/').collect::<Vec<_>>();
+    assert_eq!(2, tokens.len());
+    assert!(tokens.iter().all(|token| token.starts_with("seg_")
+        && !token.contains("genre")
+        && !token.contains("house")));
+    assert_ne!(tokens[0], tokens[1]);
+
+    // Stable tokens
+    assert_eq!(
+        anonymized,
+        Facet::from_str("genre/house@20220625").to_anonymized()
+    );
+    let other = Facet::from_str("genre/techno").to_anonymized();
+    assert_eq!(Some(tokens[0]), other.split('/').next());
+    assert!(!other.contains('@'));
+
+    assert_eq!("", Facet::from_str("").to_anonymized());
+    assert_eq!("@20220625", Facet::from_str("@20220625").to_anonymized());
+}
+
+#[test]
+fn decompose() {
+    use super::FacetParts;
+
+    let date = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    assert_eq!(
+        FacetParts {
+            category: "genre",
+            subpath: "electronic/house",
+            date: Some(date),
+            raw_date_suffix: Some("@20220625"),
+        },
+        Facet::from_str("genre/electronic/house@20220625").decompose()
+    );
+    assert_eq!(
+        FacetParts {
+            category: "genre",
+            subpath: "",
+            date: None,
+            raw_date_suffix: None,
+        },
+        Facet::from_str("genre").decompose()
+    );
+    assert_eq!(
+        FacetParts {
+            category: "played",
+            subpath: "",
+            date: None,
+            raw_date_suffix: Some("@20220230"),
+        },
+        Facet::from_str("played@20220230").decompose()
+    );
+    assert_eq!(
+        FacetParts {
+            category: "",
+            subpath: "",
+            date: Some(date),
+            raw_date_suffix: Some("@20220625"),
+        },
+        Facet::from_str("@20220625").decompose()
+    ); // Suffixes that are not date-like are part of the path
+    assert_eq!(
+        FacetParts {
+            category: "genre@abcdefgh",
+            subpath: "",
+            date: None,
+            raw_date_suffix: None,
+        },
+        Facet::from_str("genre@abcdefgh").decompose()
+    );
+    assert_eq!(
+        FacetParts {
+            category: "genre @20220625",
+            subpath: "",
+            date: None,
+            raw_date_suffix: None,
+        },
+        Facet::from_str("genre @20220625").decompose()
+    );
+}
+
+#[test]
+fn try_split_into_prefix_and_date_any_separator() {
+    use super::try_split_into_prefix_and_date_any_separator;
+
+    let date = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    for sep in super::LENIENT_DATE_SEPARATORS {
+        let facet = format!("event{sep}20220625");
+        assert_eq!(
+            Some(("event", sep, date)),
+            try_split_into_prefix_and_date_any_separator(&facet)
+        );
+    }
+    assert_eq!(
+        Some(("", '.', date)),
+        try_split_into_prefix_and_date_any_separator(".20220625")
+    );
+
+    assert_eq!(
+        None,
+        try_split_into_prefix_and_date_any_separator("event20220625")
+    );
+    assert_eq!(
+        None,
+        try_split_into_prefix_and_date_any_separator("event:20220625")
+    );
+    assert_eq!(
+        None,
+        try_split_into_prefix_and_date_any_separator("event.20220230")
+    );
+    assert_eq!(
+        None,
+        try_split_into_prefix_and_date_any_separator("event.2022062x")
+    );
+}
+
+#[test]
+fn summarize_set() {
+    let facets = [
+        Facet::from_str("genre/electronic/house"),
+        Facet::from_str("played@20220625"),
+        Facet::from_str("played@20220626"),
+        Facet::from_str("played@19700230"),
+        Facet::from_str("@20220625"),
+        Facet::from_str(""),
+    ];
+    assert_eq!(
+        "6 facets, 3 dated, 1 invalid dates, max depth 3",
+        super::summarize_set(&facets)
+    );
+    assert_eq!(
+        "0 facets, 0 dated, 0 invalid dates, max depth 0",
+        super::summarize_set::<Facet>(&[])
+    );
+}
+
+#[test]
+fn all_under_prefix() {
+    let facets = [
+        Facet::from_str("tenant/a"),
+        Facet::from_str("tenant/b@20220625"),
+        Facet::from_str("tenant"),
+    ];
+    assert_eq!(Ok(()), super::all_under_prefix(&facets, "tenant"));
+    assert_eq!(Ok(()), super::all_under_prefix(&facets, ""));
+
+    let facets = [
+        Facet::from_str("tenant/a"),
+        Facet::from_str("tenants/b"),
+        Facet::from_str("other/tenant"),
+        Facet::from_str("tenant/c"),
+    ];
+    assert_eq!(
+        Err(vec![&facets[1], &facets[2]]),
+        super::all_under_prefix(&facets, "tenant")
+    );
+}
+
+#[test]
+fn canonicalize_date_suffix() {
+    use super::canonicalize_date_suffix;
+
+    for facet in [
+        "event@2022-06-25",
+        "event-2022-06-25",
+        "event.20220625",
+        "event_20220625",
+    ] {
+        let canonical = canonicalize_date_suffix(facet);
+        assert!(matches!(canonical, Cow::Owned(_)));
+        assert_eq!("event@20220625", canonical);
+    }
+    assert_eq!(
+        "genre/house@20220625",
+        canonicalize_date_suffix("genre/house-2022-06-25")
+    );
+
+    for facet in [
+        "event@20220625",
+        "event",
+        "event-2022-02-30",
+        "event20220625",
+        "",
+    ] {
+        assert!(
+            matches!(canonicalize_date_suffix(facet), Cow::Borrowed(borrowed) if borrowed == facet)
+        );
+    }
+}
+
+#[test]
+fn date_like_suffix() {
+    let facet = Facet::from_str("played@20220625");
+    assert_eq!(Some("@20220625"), facet.date_like_suffix(true));
+    assert_eq!(Some("20220625"), facet.date_like_suffix(false));
+    let facet = Facet::from_str("played@20220230");
+    assert_eq!(Some("20220230"), facet.date_like_suffix(false));
+    let facet = Facet::from_str("played@2022062x");
+    assert_eq!(None, facet.date_like_suffix(true));
+    let facet = Facet::from_str("played");
+    assert_eq!(None, facet.date_like_suffix(true));
+    assert_eq!(None, facet.date_like_suffix(false));
+}
+
+#[test]
+fn has_temporal_suffix() {
+    assert!(Facet::from_str("played@20220625").has_temporal_suffix());
+    assert!(Facet::from_str("played@20220230").has_temporal_suffix());
+    assert!(Facet::from_str("played@2022176").has_temporal_suffix());
+    assert!(Facet::from_str("@2024366").has_temporal_suffix());
+    assert!(!Facet::from_str("played@2023366").has_temporal_suffix());
+    assert!(!Facet::from_str("played").has_temporal_suffix());
+    assert!(!Facet::from_str("played@202206").has_temporal_suffix());
+}
+
+#[test]
+fn keyed() {
+    use super::Keyed;
+
+    let facet = Keyed::new(Facet::from_str("genre/house"));
+    assert_eq!(facet, Keyed::new(Facet::from_str("genre/house")));
+    assert_ne!(facet, Keyed::new(Facet::from_str("genre/techno")));
+    assert_eq!(&Facet::from_str("genre/house"), facet.facet());
+
+    // Both facets have the same 32-bit FNV-1a hash
+    let lhs = Keyed::new(Facet::from_str("costarring"));
+    let rhs = Keyed::new(Facet::from_str("liquid"));
+    assert_eq!(lhs.key(), rhs.key());
+    assert_ne!(lhs, rhs);
+}
+
+#[test]
+fn total_cmp_agrees_with_ord() {
+    let facets = [
+        "",
+        "@20220625",
+        "Genre",
+        "genre",
+        "genre/house",
+        "genre/house-music",
+        "genre/house/deep",
+        "genre/house@20220625",
+        "genre/électronique",
+        "genres",
+    ]
+    .map(Facet::from_str);
+    for lhs in &facets {
+        for rhs in &facets {
+            assert_eq!(lhs.cmp(rhs), super::total_cmp(lhs, rhs));
+        }
+    }
+}
+
+#[test]
+fn segment() {
+    let facet = Facet::from_str("genre/electronic/house@20220625");
+    assert_eq!(Some("genre"), facet.segment(0));
+    assert_eq!(Some("electronic"), facet.segment(1));
+    assert_eq!(Some("house"), facet.segment(2));
+    assert_eq!(None, facet.segment(3));
+    assert_eq!(None, Facet::from_str("@20220625").segment(0));
+    assert_eq!(None, Facet::from_str("").segment(0));
+}
+
+#[test]
+fn facet_policy() {
+    use super::{CharSet, FacetPolicy, PolicyViolation};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<FacetPolicy>();
+
+    assert_eq!(Ok(()), FacetPolicy::default().check("any/facet"));
+    assert_eq!(
+        Err(vec![PolicyViolation::InvalidFacet(
+            FacetError::LeadingSlash
+        )]),
+        FacetPolicy::default().check("/facet")
+    );
+
+    let policy = FacetPolicy {
+        max_len: Some(20),
+        max_depth: Some(2),
+        allowed_roots: vec!["genre".to_owned(), "mood/energy".to_owned()],
+        charset: Some(
+            CharSet::new()
+                .with_ascii_range(b'a', b'z')
+                .with_ascii_chars(b"/"),
+        ),
+        require_date_suffix: true,
+    };
+    assert_eq!(Ok(()), policy.check("genre/house@20220625"));
+    assert_eq!(Ok(()), policy.check("mood/energy@20220625"));
+    assert_eq!(
+        Err(vec![PolicyViolation::MissingDateSuffix]),
+        policy.check("genre/house")
+    );
+    assert_eq!(
+        Err(vec![
+            PolicyViolation::TooLong {
+                len: 30,
+                max_len: 20
+            },
+            PolicyViolation::TooDeep {
+                depth: 3,
+                max_depth: 2
+            },
+            PolicyViolation::RootNotAllowed,
+            PolicyViolation::InvalidCharset,
+            PolicyViolation::MissingDateSuffix,
+        ]),
+        policy.check("mood/Chill/late night@20220230")
+    );
+}
+
+#[test]
+fn rebase_date_separator() {
+    use super::rebase_date_separator;
+
+    assert_eq!(
+        "event~party@20220625",
+        rebase_date_separator("event~party~20220625", '~', '@')
+    );
+    assert_eq!("@20220625", rebase_date_separator("~20220625", '~', '@'));
+    assert_eq!(
+        "event~20220625",
+        rebase_date_separator("event@20220625", '@', '~')
+    );
+
+    for facet in [
+        "event~party",
+        "event@20220625",
+        "event~2022062x",
+        "event~202206250",
+        "",
+    ] {
+        assert!(
+            matches!(rebase_date_separator(facet, '~', '@'), Cow::Borrowed(borrowed) if borrowed == facet)
+        );
+    }
+}
+
+#[test]
+fn display_label() {
+    assert_eq!("House", Facet::from_str("house").display_label());
+    assert_eq!("House", Facet::from_str("genre/house").display_label());
+    assert_eq!(
+        "Deep House",
+        Facet::from_str("genre/house/deep house").display_label()
+    );
+    assert_eq!(
+        "Event (2022-06-25)",
+        Facet::from_str("event@20220625").display_label()
+    );
+    assert_eq!("Event", Facet::from_str("event@20220230").display_label());
+    assert_eq!("2022-06-25", Facet::from_str("@20220625").display_label());
+    assert_eq!("", Facet::from_str("").display_label());
+}
+
+#[test]
+fn matches_prefix_and_date() {
+    use super::matches_prefix_and_date;
+
+    let dates = [
+        Date::from_calendar_date(2022, time::Month::June, 25).unwrap(),
+        Date::from_calendar_date(2022, time::Month::June, 26).unwrap(),
+        Date::from_calendar_date(1, time::Month::January, 1).unwrap(),
+        Date::from_calendar_date(-1, time::Month::January, 1).unwrap(),
+    ];
+    let facets = [
+        "played@20220625",
+        "played@20220626",
+        "played@00010101",
+        "@20220625",
+        "played@2022062",
+        "played@202206250",
+        "played",
+        "",
+    ];
+    for facet in facets {
+        for prefix in ["played", "", "play"] {
+            for date in dates {
+                let expected = Facet::from_prefix_with_date_suffix(prefix, date)
+                    .is_ok_and(|constructed| &*constructed == facet);
+                assert_eq!(
+                    expected,
+                    matches_prefix_and_date(facet, prefix, date),
+                    "{facet} {prefix} {date}"
+                );
+            }
+        }
+    }
+    assert!(matches_prefix_and_date(
+        "played@20220625",
+        "played",
+        dates[0]
+    ));
+}
+
+#[test]
+fn try_split_off_version_segment() {
+    assert_eq!(
+        ("playlist/summer", Some(3)),
+        Facet::from_str("playlist/summer/v3").try_split_off_version_segment()
+    );
+    assert_eq!(
+        ("playlist/summer", Some(12)),
+        Facet::from_str("playlist/summer/v12@20220625").try_split_off_version_segment()
+    );
+    assert_eq!(
+        ("", Some(1)),
+        Facet::from_str("v1").try_split_off_version_segment()
+    );
+    assert_eq!(
+        ("playlist/summer", None),
+        Facet::from_str("playlist/summer@20220625").try_split_off_version_segment()
+    );
+    for facet in [
+        "playlist/v",
+        "playlist/vx1",
+        "playlist/v1x",
+        "playlist/v99999999999",
+    ] {
+        assert_eq!(
+            (facet, None),
+            Facet::from_str(facet).try_split_off_version_segment()
+        );
+    }
+    // Only the last segment is considered
+    assert_eq!(
+        ("playlist/v3/summer", None),
+        Facet::from_str("playlist/v3/summer").try_split_off_version_segment()
+    );
+}
+
+#[test]
+fn sets_equal_and_symmetric_difference() {
+    use super::{sets_equal, symmetric_difference};
+
+    let lhs = ["genre/house", "played@20220625", "mood"].map(Facet::from_str);
+    let reordered = ["mood", "genre/house", "played@20220625"].map(Facet::from_str);
+    let duplicates =
+        ["mood", "genre/house", "mood", "played@20220625", "mood"].map(Facet::from_str);
+    assert!(sets_equal(&lhs, &reordered));
+    assert!(symmetric_difference(&lhs, &reordered).is_empty());
+    assert!(sets_equal(&lhs, &duplicates));
+    assert!(symmetric_difference(&duplicates, &lhs).is_empty());
+
+    let rhs = ["genre/techno", "mood", "played@20220626", "genre/techno"].map(Facet::from_str);
+    assert!(!sets_equal(&lhs, &rhs));
+    assert_eq!(
+        vec![&lhs[0], &lhs[1], &rhs[0], &rhs[2]],
+        symmetric_difference(&lhs, &rhs)
+    );
+    assert!(!sets_equal(&lhs, &[]));
+    assert!(sets_equal::<Facet>(&[], &[]));
+}
+
+#[test]
+fn find_orphans() {
+    let facets = [
+        "genre",
+        "genre/electronic/house",
+        "genre/electronic/techno",
+        "genre/jazz/fusion",
+        "genre/jazz",
+        "mood/chll/relaxed@20220625",
+        "mood/chll/relaxed@20220626",
+        "mood/energetic",
+        "venue@20220625",
+    ]
+    .map(Facet::from_str);
+    assert_eq!(vec![&facets[5], &facets[6]], super::find_orphans(&facets));
+    assert!(super::find_orphans::<Facet>(&[]).is_empty());
+}
+
+#[test]
+fn breadcrumbs() {
+    let facet = Facet::from_str("genre/electronic/deep house@20220625");
+    assert_eq!(
+        "Genre › Electronic › Deep House",
+        facet.breadcrumbs(" › ", false)
+    );
+    assert_eq!(
+        "Genre/Electronic/Deep House (2022-06-25)",
+        facet.breadcrumbs("/", true)
+    );
+    assert_eq!("Genre", Facet::from_str("genre").breadcrumbs(" › ", true));
+    assert_eq!(
+        "Played",
+        Facet::from_str("played@20220230").breadcrumbs(" › ", true)
+    );
+    assert_eq!(
+        "2022-06-25",
+        Facet::from_str("@20220625").breadcrumbs(" › ", true)
+    );
+    assert_eq!("", Facet::from_str("@20220625").breadcrumbs(" › ", false));
+}
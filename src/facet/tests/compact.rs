@@ -0,0 +1,708 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+// Test cases for features that are only provided by `CompactFacet`.
+
+use std::borrow::Cow;
+
+use compact_str::CompactString;
+use time::Date;
+
+use super::{cases::FixedClock, Facet, FacetBuild as _, FacetError, FacetRef as _};
+
+#[test]
+fn from_lines() {
+    let text = "# Comment\n\ngenre/house\n  \n/invalid\n  # Indented comment\nwishlist@20220625\r\nplayed @20220625\n";
+    assert_eq!(
+        vec![
+            Ok(Facet::from_str("genre/house")),
+            Err((5, FacetError::LeadingSlash)),
+            Ok(Facet::from_str("wishlist@20220625")),
+            Err((8, FacetError::InvalidDateLikeSuffix)),
+        ],
+        Facet::from_lines(text)
+    );
+}
+
+#[test]
+fn eq_compact_string() {
+    let facet = Facet::from_str("genre/house");
+    assert!(facet == CompactString::from("genre/house"));
+    assert!(CompactString::from("genre/house") == facet);
+    assert!(facet != CompactString::from("genre/House"));
+    assert!(CompactString::from("genre/House") != facet);
+}
+
+#[test]
+fn try_from_bytes() {
+    use super::FacetFromBytesError;
+
+    assert_eq!(
+        Ok(Facet::from_str("genre/house")),
+        Facet::try_from(b"genre/house".as_slice())
+    );
+    assert_eq!(
+        Err(FacetFromBytesError::InvalidFacet(FacetError::LeadingSlash)),
+        Facet::try_from(b"/genre/house".as_slice())
+    );
+    assert!(matches!(
+        Facet::try_from(b"genre/\xff".as_slice()),
+        Err(FacetFromBytesError::InvalidUtf8(_))
+    ));
+}
+
+#[test]
+fn from_prefix_with_ymd() {
+    use super::DatedFacetError;
+    assert_eq!(
+        Facet::from_str("wishlist@20220625"),
+        Facet::from_prefix_with_ymd("wishlist", 2022, 6, 25).unwrap()
+    );
+    assert_eq!(
+        Facet::from_str("@20240229"),
+        Facet::from_prefix_with_ymd("", 2024, 2, 29).unwrap()
+    );
+    assert!(matches!(
+        Facet::from_prefix_with_ymd("wishlist", 2022, 2, 30),
+        Err(DatedFacetError::InvalidDate(err)) if err.name() == "day"
+    ));
+    assert!(matches!(
+        Facet::from_prefix_with_ymd("wishlist", 2022, 13, 1),
+        Err(DatedFacetError::InvalidDate(err)) if err.name() == "month"
+    ));
+    assert!(matches!(
+        Facet::from_prefix_with_ymd("wishlist", 2022, 0, 1),
+        Err(DatedFacetError::InvalidDate(_))
+    ));
+    assert!(matches!(
+        Facet::from_prefix_with_ymd("/wishlist", 2022, 6, 25),
+        Err(DatedFacetError::InvalidPrefix(FacetError::LeadingSlash))
+    ));
+    assert!(matches!(
+        Facet::from_prefix_with_ymd("wishlist", -1, 6, 25),
+        Err(DatedFacetError::Format(_))
+    ));
+}
+
+#[test]
+fn merge_keep_later_date() {
+    use super::merge_keep_later_date;
+    assert_eq!(
+        Some(Facet::from_str("played@20220626")),
+        merge_keep_later_date("played@20220625", "played@20220626")
+    );
+    assert_eq!(
+        Some(Facet::from_str("played@20220626")),
+        merge_keep_later_date("played@20220626", "played@20220625")
+    );
+    // Prefer the dated facet
+    assert_eq!(
+        Some(Facet::from_str("played@20220625")),
+        merge_keep_later_date("played", "played@20220625")
+    );
+    assert_eq!(
+        Some(Facet::from_str("played@20220625")),
+        merge_keep_later_date("played@20220625", "played@19700230")
+    );
+    assert_eq!(
+        Some(Facet::from_str("played")),
+        merge_keep_later_date("played", "played")
+    );
+    assert_eq!(
+        None,
+        merge_keep_later_date("played@20220625", "wishlist@20220626")
+    );
+}
+
+#[test]
+fn with_date_suffix_if_absent() {
+    use super::{with_date_suffix_if_absent, DatedFacetError};
+    let date = Date::from_calendar_date(2022, time::Month::June, 25).unwrap();
+    assert_eq!(
+        Facet::from_str("played@20220625"),
+        with_date_suffix_if_absent("played", date).unwrap()
+    );
+    assert_eq!(
+        Facet::from_str("played@20220101"),
+        with_date_suffix_if_absent("played@20220101", date).unwrap()
+    );
+    assert_eq!(
+        Facet::from_str("@20220625"),
+        with_date_suffix_if_absent("", date).unwrap()
+    );
+    assert!(matches!(
+        with_date_suffix_if_absent("played ", date),
+        Err(DatedFacetError::InvalidPrefix(
+            FacetError::LeadingOrTrailingWhitespace
+        ))
+    ));
+    assert!(matches!(
+        with_date_suffix_if_absent("played @20220101", date),
+        Err(DatedFacetError::InvalidPrefix(
+            FacetError::InvalidDateLikeSuffix
+        ))
+    ));
+}
+
+#[test]
+fn try_repair_date_suffix() {
+    use super::try_repair_date_suffix;
+
+    // Trailing separator
+    assert_eq!(
+        Some(Facet::from_str("played@20220625")),
+        try_repair_date_suffix("played20220625@")
+    );
+    assert_eq!(
+        Some(Facet::from_str("@20220625")),
+        try_repair_date_suffix("20220625@")
+    );
+    assert_eq!(None, try_repair_date_suffix("2022062@"));
+    // The digits must not be preceded by a partial character
+    assert_eq!(None, try_repair_date_suffix("\u{e9}2022062@"));
+    // Missing leading zero of the month
+    assert_eq!(
+        Some(Facet::from_str("played@20220625")),
+        try_repair_date_suffix("played@2022625")
+    );
+    // Duplicated digit
+    assert_eq!(
+        Some(Facet::from_str("played@20220625")),
+        try_repair_date_suffix("played@202206625")
+    );
+    assert_eq!(
+        Some(Facet::from_str("played@20221001")),
+        try_repair_date_suffix("played@202210001")
+    );
+    // Ambiguous: 2022-01-22 or 2022-01-12?
+    assert_eq!(None, try_repair_date_suffix("played@202201122"));
+    // Valid ordinal date
+    assert_eq!(None, try_repair_date_suffix("played@2022125"));
+    // Invalid calendar dates
+    assert_eq!(None, try_repair_date_suffix("played@2022135"));
+    assert_eq!(None, try_repair_date_suffix("played20221399@"));
+    // Nothing to repair
+    assert_eq!(None, try_repair_date_suffix("played@20220625"));
+    assert_eq!(None, try_repair_date_suffix("played"));
+    assert_eq!(None, try_repair_date_suffix("played@"));
+}
+
+/// Set or remove an environment variable and restore it when dropped.
+#[cfg(feature = "std")]
+struct ScopedEnvVar {
+    name: &'static str,
+    previous: Option<std::ffi::OsString>,
+}
+
+#[cfg(feature = "std")]
+impl ScopedEnvVar {
+    fn new(name: &'static str, value: Option<&str>) -> Self {
+        let previous = std::env::var_os(name);
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+        Self { name, previous }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for ScopedEnvVar {
+    fn drop(&mut self) {
+        match self.previous.take() {
+            Some(previous) => std::env::set_var(self.name, previous),
+            None => std::env::remove_var(self.name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn from_env() {
+    use super::FacetFromEnvError;
+
+    // The variable is only used by this test to avoid interference
+    // with other tests that run concurrently.
+    const VAR: &str = "GIGTAGS_TEST_FACET_FROM_ENV";
+
+    {
+        let _var = ScopedEnvVar::new(VAR, None);
+        assert_eq!(Ok(None), Facet::from_env(VAR));
+    }
+    {
+        let _var = ScopedEnvVar::new(VAR, Some("genre/house@20220625"));
+        assert_eq!(
+            Ok(Some(Facet::from_str("genre/house@20220625"))),
+            Facet::from_env(VAR)
+        );
+    }
+    {
+        let _var = ScopedEnvVar::new(VAR, Some("/genre/house"));
+        let err = Facet::from_env(VAR).unwrap_err();
+        assert_eq!(r#"invalid facet "/genre/house""#, err.to_string());
+        assert_eq!(
+            FacetFromEnvError::InvalidFacet {
+                value: "/genre/house".to_owned(),
+                error: FacetError::LeadingSlash,
+            },
+            err
+        );
+    }
+    assert!(std::env::var_os(VAR).is_none());
+}
+
+#[test]
+fn facet_scanner() {
+    use std::fmt::Write as _;
+
+    use super::FacetScanner;
+
+    let mut scanner = FacetScanner::new();
+    assert!(scanner.is_currently_valid());
+    scanner.push_str("genre");
+    assert!(scanner.is_currently_valid());
+    scanner.push_char('/');
+    assert!(scanner.is_currently_valid());
+    scanner.push_str("house ");
+    assert_eq!(
+        Err(FacetError::LeadingOrTrailingWhitespace),
+        scanner.validate()
+    );
+    write!(scanner, "@{}", 20_220_625).unwrap();
+    assert_eq!(Err(FacetError::InvalidDateLikeSuffix), scanner.validate());
+    scanner.push_str("0");
+    assert!(scanner.is_currently_valid());
+    assert_eq!("genre/house @202206250", scanner.as_str());
+    assert_eq!(
+        Ok(Facet::from_str("genre/house @202206250")),
+        scanner.finish()
+    );
+
+    let mut scanner = FacetScanner::new();
+    scanner.push_str("");
+    assert!(scanner.is_currently_valid());
+    scanner.push_str("/genre");
+    assert_eq!(Err(FacetError::LeadingSlash), scanner.clone().finish());
+    let mut scanner = FacetScanner::new();
+    scanner.push_char(' ');
+    scanner.push_str("genre");
+    assert_eq!(
+        Err(FacetError::LeadingOrTrailingWhitespace),
+        scanner.finish()
+    );
+}
+
+#[test]
+fn top_level_categories() {
+    let facets = [
+        Facet::from_str("mood/chill"),
+        Facet::from_str("genre/techno"),
+        Facet::from_str(""),
+        Facet::from_str("genre/house@20220625"),
+        Facet::from_str("@20220625"),
+        Facet::from_str("genre"),
+    ];
+    assert_eq!(
+        vec![Facet::from_str("genre"), Facet::from_str("mood")],
+        super::top_level_categories(&facets)
+    );
+    assert!(super::top_level_categories::<Facet>(&[]).is_empty());
+}
+
+#[test]
+fn set_date_suffix_day() {
+    use super::{set_date_suffix_day, DatedFacetError};
+
+    assert_eq!(
+        Some(Facet::from_str("played@20220601")),
+        set_date_suffix_day("played@20220625", 1).unwrap()
+    );
+    assert_eq!(
+        Some(Facet::from_str("@20220630")),
+        set_date_suffix_day("@20220625", 30).unwrap()
+    );
+    assert!(matches!(
+        set_date_suffix_day("played@20220225", 31),
+        Err(DatedFacetError::InvalidDate(_))
+    ));
+    assert_eq!(None, set_date_suffix_day("played", 1).unwrap());
+    assert_eq!(None, set_date_suffix_day("played@19700230", 1).unwrap());
+}
+
+#[test]
+fn render_template_with_clock() {
+    use super::{render_template_with_clock, DatedFacetError};
+
+    let clock = FixedClock(Date::from_calendar_date(2022, time::Month::June, 25).unwrap());
+    assert_eq!(
+        Facet::from_str("chart@20220625"),
+        render_template_with_clock("chart{@now}", &clock).unwrap()
+    );
+    assert_eq!(
+        Facet::from_str("chart"),
+        render_template_with_clock("chart", &clock).unwrap()
+    );
+    assert!(matches!(
+        render_template_with_clock("chart {@now}", &clock),
+        Err(DatedFacetError::InvalidRendered(
+            FacetError::InvalidDateLikeSuffix
+        ))
+    ));
+    assert!(matches!(
+        render_template_with_clock("/chart{@now}", &clock),
+        Err(DatedFacetError::InvalidRendered(FacetError::LeadingSlash))
+    ));
+    let clock = FixedClock(Date::from_calendar_date(-1, time::Month::January, 1).unwrap());
+    assert!(matches!(
+        render_template_with_clock("chart{@now}", &clock),
+        Err(DatedFacetError::Format(_))
+    ));
+}
+
+#[test]
+fn date_suffix_to_prefix() {
+    use super::date_suffix_to_prefix;
+
+    assert_eq!(
+        Some(Facet::from_str("20220625-event")),
+        date_suffix_to_prefix("event@20220625", '-')
+    );
+    assert_eq!(
+        Some(Facet::from_str("20220625_genre/house")),
+        date_suffix_to_prefix("genre/house@20220625", '_')
+    );
+    assert_eq!(
+        Some(Facet::from_str("20220625")),
+        date_suffix_to_prefix("@20220625", '-')
+    );
+    assert_eq!(None, date_suffix_to_prefix("event", '-'));
+    assert_eq!(None, date_suffix_to_prefix("event@19700230", '-'));
+}
+
+#[test]
+fn lookup() {
+    use std::collections::HashMap;
+
+    let map = [
+        (Facet::from_str("genre/house"), 1),
+        (Facet::from_str("played@20220625"), 2),
+    ]
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+    let key: Cow<'_, str> = Cow::Owned("played@20220625".to_owned());
+    assert_eq!(Some(&2), super::lookup(&map, &key));
+    assert_eq!(Some(&1), map.get::<str>(&Cow::Borrowed("genre/house")));
+    assert_eq!(None, super::lookup(&map, "genre"));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn json_schema_has_pattern_but_no_max_length() {
+    use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema as _};
+    let Schema::Object(schema) = super::CompactFacet::json_schema(&mut SchemaGenerator::default())
+    else {
+        panic!("no schema object");
+    };
+    let string = schema.string.expect("string validation");
+    assert_eq!(
+        Some(super::VALID_FACET_REGEX_STR),
+        string.pattern.as_deref()
+    );
+    assert_eq!(None, string.max_length);
+}
+
+#[test]
+fn hash_facet_bytes() {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use super::{CompactFacet, StdFacet};
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    for facet in [
+        "",
+        "genre/house",
+        "played@20220625",
+        "a long facet that is not inlined",
+    ] {
+        let compact_facet = CompactFacet::from_str(facet);
+        let std_facet = StdFacet::from_str(facet);
+        assert_eq!(&*compact_facet, &*std_facet);
+        assert_eq!(hash(facet), hash(&compact_facet));
+        assert_eq!(hash(facet), hash(&std_facet));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_facets_joined() {
+    use super::joined;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Track {
+        #[serde(with = "joined")]
+        facets: Vec<Facet>,
+    }
+
+    let track = Track {
+        facets: vec![
+            Facet::from_str("genre/house"),
+            Facet::from_str("played@20220625"),
+        ],
+    };
+    let json = serde_json::json!({ "facets": "genre/house played@20220625" });
+    assert_eq!(json, serde_json::to_value(&track).unwrap());
+    assert_eq!(track, serde_json::from_value::<Track>(json).unwrap());
+    assert_eq!(
+        track,
+        serde_json::from_value::<Track>(
+            serde_json::json!({ "facets": "genre/house  played@20220625" })
+        )
+        .unwrap()
+    );
+    assert!(
+        serde_json::from_value::<Track>(serde_json::json!({ "facets": "/genre/house" })).is_err()
+    );
+    assert!(serde_json::to_value(Track {
+        facets: vec![Facet::from_str("genre/deep house")]
+    })
+    .is_err());
+    assert!(serde_json::to_value(Track {
+        facets: vec![Facet::default()]
+    })
+    .is_err());
+}
+
+#[test]
+fn join() {
+    use super::{join, JoinError};
+
+    assert_eq!(
+        "genre/electronic/house",
+        &*join("genre/electronic", "house").unwrap()
+    );
+    assert_eq!(
+        "genre/house@20220625",
+        &*join("genre", "house@20220625").unwrap()
+    );
+    assert_eq!("house", &*join("", "house").unwrap());
+    assert_eq!("genre", &*join("genre", "").unwrap());
+    assert_eq!("", &*join("", "").unwrap());
+
+    // A date-only relative facet dates the base
+    assert_eq!("genre@20220625", &*join("genre", "@20220625").unwrap());
+    assert_eq!("@20220625", &*join("", "@20220625").unwrap());
+
+    assert_eq!(
+        Err(JoinError::InvalidFacet(FacetError::LeadingSlash)),
+        join("genre", "/house")
+    );
+    assert_eq!(
+        Err(JoinError::InvalidFacet(
+            FacetError::LeadingOrTrailingWhitespace
+        )),
+        join("genre", " house")
+    );
+    assert_eq!(Err(JoinError::DatedBase), join("played@20220625", "house"));
+    assert_eq!(Err(JoinError::DatedBase), join("@20220625", ""));
+}
+
+#[test]
+fn clear_and_set_from_retain_capacity() {
+    let mut facet = super::CompactFacet::from_str("genre/electronic/house/deep@20220625");
+    let capacity = facet.0.capacity();
+    facet.clear();
+    assert!(facet.is_empty());
+    assert_eq!(capacity, facet.0.capacity());
+    facet.set_from("genre/electronic/house/acid@20220626");
+    assert_eq!("genre/electronic/house/acid@20220626", &*facet);
+    assert_eq!(capacity, facet.0.capacity());
+}
+
+#[test]
+fn convert_between_facet_types() {
+    use super::{facet_from, CompactFacet, StdFacet};
+
+    let std_facet = StdFacet::from_str("genre/house@20220625");
+    let compact_facet: CompactFacet = facet_from(&std_facet);
+    assert_eq!(&*std_facet, &*compact_facet);
+    assert_eq!(std_facet, compact_facet.to_facet::<StdFacet>());
+}
+
+#[test]
+fn rewrite() {
+    use super::{canonicalize_date_suffix, CompactFacet};
+
+    let facets = vec![
+        Facet::from_str("played.20220625"),
+        Facet::from_str("obsolete"),
+        Facet::from_str("genre/house"),
+        Facet::from_str("played-2022-06-26"),
+    ];
+    let rewritten = super::rewrite(facets, |facet| {
+        (facet != "obsolete").then(|| CompactFacet::from_cow_str(canonicalize_date_suffix(facet)))
+    });
+    assert_eq!(
+        vec![
+            CompactFacet::from_str("played@20220625"),
+            CompactFacet::from_str("genre/house"),
+            CompactFacet::from_str("played@20220626"),
+        ],
+        rewritten
+    );
+}
+
+#[test]
+fn touch_all() {
+    use super::{touch_all, CompactFacet, DatedFacetError};
+
+    let clock = FixedClock(Date::from_calendar_date(2022, time::Month::June, 25).unwrap());
+    let mut facets = vec![
+        CompactFacet::from_str("played@20200101"),
+        CompactFacet::from_str("genre/house"),
+        CompactFacet::from_str("seen@20220625"),
+        CompactFacet::from_str("invalid@20200230"),
+        CompactFacet::from_str(""),
+    ];
+    touch_all(&mut facets, &clock).unwrap();
+    let expected = vec![
+        CompactFacet::from_str("played@20220625"),
+        CompactFacet::from_str("genre/house@20220625"),
+        CompactFacet::from_str("seen@20220625"),
+        CompactFacet::from_str("invalid@20220625"),
+        CompactFacet::from_str("@20220625"),
+    ];
+    assert_eq!(expected, facets);
+    // Idempotent
+    touch_all(&mut facets, &clock).unwrap();
+    assert_eq!(expected, facets);
+
+    let mut facets = vec![
+        CompactFacet::from_str("played"),
+        CompactFacet::from_str("/played"),
+    ];
+    assert!(matches!(
+        touch_all(&mut facets, &clock),
+        Err(DatedFacetError::InvalidPrefix(FacetError::LeadingSlash))
+    ));
+    assert_eq!(CompactFacet::from_str("played"), facets[0]);
+}
+
+#[test]
+fn from_query_string() {
+    use super::{CompactFacet, FacetFromBytesError};
+
+    assert_eq!(
+        Ok(vec![
+            CompactFacet::from_str("genre/house"),
+            CompactFacet::from_str("mood/chill out@20220625"),
+        ]),
+        CompactFacet::from_query_string(
+            "facet=genre%2Fhouse&label=ignored&facet=mood%2Fchill%20out%4020220625",
+            "facet"
+        )
+    );
+    assert_eq!(
+        Ok(vec![]),
+        CompactFacet::from_query_string("label=genre%2Fhouse", "facet")
+    );
+    assert_eq!(Ok(vec![]), CompactFacet::from_query_string("", "facet"));
+    assert_eq!(
+        Err(FacetFromBytesError::InvalidFacet(FacetError::LeadingSlash)),
+        CompactFacet::from_query_string("facet=genre&facet=%2Fhouse", "facet")
+    );
+    assert!(matches!(
+        CompactFacet::from_query_string("facet=%FF", "facet"),
+        Err(FacetFromBytesError::InvalidUtf8(_))
+    ));
+    // Form encoding of spaces
+    assert_eq!(
+        Ok(vec![
+            CompactFacet::from_str("mood/chill out"),
+            CompactFacet::from_str("genre/drum+bass"),
+        ]),
+        CompactFacet::from_query_string(
+            "my+facet=mood%2Fchill+out&my%20facet=genre%2Fdrum%2Bbass&my%2Bfacet=ignored",
+            "my facet"
+        )
+    );
+}
+
+#[test]
+fn dedup_by_topic_keep_latest() {
+    use super::CompactFacet;
+
+    let facets = [
+        "played",
+        "genre/house",
+        "played@20220625",
+        "played@20220627",
+        "played@20220230",
+        "played@20220626",
+        "wishlist@20220601",
+        "wishlist@20220601",
+        "genre/house",
+    ]
+    .map(Facet::from_str);
+    assert_eq!(
+        vec![
+            CompactFacet::from_str("played@20220627"),
+            CompactFacet::from_str("genre/house"),
+            CompactFacet::from_str("wishlist@20220601"),
+        ],
+        super::dedup_by_topic_keep_latest(facets)
+    );
+}
+
+#[test]
+fn encode_and_decode_binary() {
+    use super::{decode_binary, BinaryDecodeError, CompactFacet};
+
+    let long_facet = "genre/electronic/".repeat(10);
+    let facets = [
+        "",
+        "genre/house@20220625",
+        "@20220625",
+        "genre/électronique",
+        long_facet.as_str(),
+    ]
+    .map(CompactFacet::from_str);
+    let mut encoded = Vec::new();
+    for facet in &facets {
+        facet.encode_binary(&mut encoded);
+    }
+    // Multi-byte varint for the long facet
+    assert!(long_facet.len() > 0x7f);
+    let mut remainder = encoded.as_slice();
+    for facet in &facets {
+        let (decoded, next_remainder) = decode_binary(remainder).unwrap();
+        assert_eq!(facet, &decoded);
+        remainder = next_remainder;
+    }
+    assert!(remainder.is_empty());
+
+    assert_eq!(Err(BinaryDecodeError::Truncated), decode_binary(&[]));
+    assert_eq!(Err(BinaryDecodeError::Truncated), decode_binary(&[0x80]));
+    assert_eq!(Err(BinaryDecodeError::Truncated), decode_binary(&[2, b'a']));
+    assert_eq!(
+        Err(BinaryDecodeError::LengthOverflow),
+        decode_binary(&[0xff; 11])
+    );
+    assert!(matches!(
+        decode_binary(&[1, 0xff]),
+        Err(BinaryDecodeError::InvalidUtf8(_))
+    ));
+    assert_eq!(
+        Err(BinaryDecodeError::InvalidFacet(
+            FacetError::LeadingOrTrailingWhitespace
+        )),
+        decode_binary(b"\x02a ")
+    );
+}
@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::collections::BTreeMap;
+
+use super::FacetRef;
+
+/// A node in a tree of facets
+///
+/// Each node represents a path segment. Facets are attached to the node
+/// of their last segment, i.e. facets that only differ by their date-like
+/// suffix end up at the same node. The root node has an empty segment
+/// and holds all facets without any segments.
+#[derive(Debug)]
+pub struct FacetTree<'a, F> {
+    segment: &'a str,
+    facets: Vec<&'a F>,
+    children: BTreeMap<&'a str, FacetTree<'a, F>>,
+}
+
+impl<'a, F> FacetTree<'a, F>
+where
+    F: FacetRef,
+{
+    const fn new(segment: &'a str) -> Self {
+        Self {
+            segment,
+            facets: Vec::new(),
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Build a tree from the given facets.
+    #[must_use]
+    pub fn build(facets: &'a [F]) -> Self {
+        let mut root = Self::new("");
+        for facet in facets {
+            root.insert(facet);
+        }
+        root
+    }
+
+    fn insert(&mut self, facet: &'a F) {
+        let mut node = self;
+        for segment in facet.segments() {
+            node = node
+                .children
+                .entry(segment)
+                .or_insert_with(|| Self::new(segment));
+        }
+        node.facets.push(facet);
+    }
+
+    /// The path segment of this node.
+    ///
+    /// Empty for the root node.
+    #[must_use]
+    pub const fn segment(&self) -> &'a str {
+        self.segment
+    }
+
+    /// The facets that terminate at this node, in insertion order.
+    #[must_use]
+    pub fn facets(&self) -> &[&'a F] {
+        &self.facets
+    }
+
+    /// The child nodes, ordered by their segment.
+    pub fn children(&self) -> impl Iterator<Item = &Self> {
+        self.children.values()
+    }
+
+    /// Find the child node with the given segment.
+    #[must_use]
+    pub fn child(&self, segment: &str) -> Option<&Self> {
+        self.children.get(segment)
+    }
+}
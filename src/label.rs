@@ -5,6 +5,7 @@
 
 use std::{borrow::Cow, ops::Deref};
 
+#[cfg(feature = "compact_str")]
 use compact_str::CompactString;
 
 /// Check if the given label is valid.
@@ -23,6 +24,11 @@ pub fn is_empty(label: &str) -> bool {
 }
 
 /// Common trait for labels
+///
+/// All label types of this crate hash purely on their UTF-8 bytes, i.e.
+/// identically to the corresponding [`str`] and to facets with identical
+/// bytes. See [`Facet`](crate::facet::Facet) for how to mix facets and
+/// labels as keys of the same map.
 pub trait Label: AsRef<str> + Default + PartialEq + Ord + Sized {
     /// Crate a label from a borrowed string slice.
     ///
@@ -60,10 +66,12 @@ pub trait Label: AsRef<str> + Default + PartialEq + Ord + Sized {
 }
 
 /// Label with a `CompactString` representation
+#[cfg(feature = "compact_str")]
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(clippy::module_name_repetitions)]
 pub struct CompactLabel(CompactString);
 
+#[cfg(feature = "compact_str")]
 impl CompactLabel {
     /// Create a new label.
     ///
@@ -74,12 +82,14 @@ impl CompactLabel {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactString> for CompactLabel {
     fn from(from: CompactString) -> Self {
         Self::new(from)
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactLabel> for CompactString {
     fn from(from: CompactLabel) -> Self {
         let CompactLabel(inner) = from;
@@ -87,12 +97,14 @@ impl From<CompactLabel> for CompactString {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl AsRef<str> for CompactLabel {
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl Deref for CompactLabel {
     type Target = str;
 
@@ -101,6 +113,7 @@ impl Deref for CompactLabel {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl Label for CompactLabel {
     fn from_str(label: &str) -> Self {
         Self(label.into())
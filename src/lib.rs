@@ -21,6 +21,7 @@ pub mod docs {
 
     // TODO: README.md does not contain any Rust code blocks!?
     #![allow(rustdoc::invalid_rust_codeblocks)]
+    #![allow(clippy::doc_markdown)]
     #![doc = include_str!("../README.md")]
 }
 
@@ -36,7 +37,7 @@ use thiserror::Error;
 use url::Url;
 
 pub mod facet;
-use self::facet::Facet;
+use self::facet::{Facet, FacetBuild};
 
 pub mod label;
 use self::label::Label;
@@ -44,6 +45,11 @@ use self::label::Label;
 pub mod props;
 use self::props::Property;
 
+pub mod stream;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 /// A tag
 pub struct Tag<F, L, N, V> {
@@ -112,6 +118,28 @@ where
     }
 }
 
+impl<F, L, N, V> Tag<F, L, N, V>
+where
+    N: props::Name,
+    V: props::Value,
+{
+    /// [`props::score()`]
+    #[must_use]
+    pub fn score(&self) -> Option<f64> {
+        props::score(&self.props)
+    }
+
+    /// [`props::set_score()`]
+    pub fn set_score(&mut self, score: f64) {
+        props::set_score(&mut self.props, score);
+    }
+
+    /// [`props::sort_canonically()`]
+    pub fn sort_props_canonically(&mut self) {
+        props::sort_canonically(&mut self.props);
+    }
+}
+
 mod encoding {
     use percent_encoding::{AsciiSet, CONTROLS};
 
@@ -230,6 +258,66 @@ impl From<url::ParseError> for DecodeError {
     }
 }
 
+impl From<std::io::Error> for DecodeError {
+    fn from(from: std::io::Error) -> Self {
+        anyhow::Error::from(from).into()
+    }
+}
+
+/// Reasons why a tag is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum TagError {
+    /// Invalid facet.
+    #[error("invalid facet")]
+    InvalidFacet(#[source] facet::FacetError),
+
+    /// Invalid label.
+    #[error("invalid label")]
+    InvalidLabel,
+
+    /// Empty or invalid property name.
+    #[error("invalid property name at index {index}")]
+    InvalidPropertyName {
+        /// The index of the property.
+        index: usize,
+    },
+
+    /// Neither a label, nor a facet with properties or a date-like suffix.
+    #[error("incomplete")]
+    Incomplete,
+}
+
+/// Validate all components of a tag.
+///
+/// The facet, the label, and the property names are validated in
+/// this order before checking the tag as a whole.
+///
+/// # Errors
+///
+/// Returns the first [`TagError`] that has been encountered.
+pub fn validate_tag<F, L, N, V>(tag: &Tag<F, L, N, V>) -> Result<(), TagError>
+where
+    F: Facet,
+    L: Label,
+    N: props::Name,
+{
+    facet::validate(tag.facet.as_ref()).map_err(TagError::InvalidFacet)?;
+    if !tag.label.is_valid() {
+        return Err(TagError::InvalidLabel);
+    }
+    if let Some(index) = tag
+        .props
+        .iter()
+        .position(|prop| !prop.name.is_valid() || prop.name.is_empty())
+    {
+        return Err(TagError::InvalidPropertyName { index });
+    }
+    if !tag.is_valid() {
+        return Err(TagError::Incomplete);
+    }
+    Ok(())
+}
+
 static DUMMY_BASE_URL_WITH_ABSOLUTE_PATH: OnceCell<Url> = OnceCell::new();
 
 fn dummy_base_url() -> &'static Url {
@@ -303,9 +391,7 @@ where
             let query_encoded = query.as_bytes();
             for name_value_encoded in query_encoded.split(|b| *b == b'&') {
                 let mut name_value_encoded_split = name_value_encoded.split(|b| *b == b'=');
-                let name_encoded = if let Some(name_encoded) = name_value_encoded_split.next() {
-                    name_encoded
-                } else {
+                let Some(name_encoded) = name_value_encoded_split.next() else {
                     return Err(anyhow::anyhow!("missing property name").into());
                 };
                 let value_encoded = name_value_encoded_split.next().unwrap_or_default();
@@ -332,7 +418,7 @@ where
         }
         let tag = Self {
             label: <L as Label>::from_cow_str(label),
-            facet: <F as Facet>::from_cow_str(facet),
+            facet: <F as FacetBuild>::from_cow_str(facet),
             props,
         };
         if !tag.is_valid() {
@@ -387,15 +473,17 @@ where
             if remainder.is_empty() {
                 break;
             }
-            let (next_remainder, next_token) =
-                if let Some((i, _)) = remainder.rmatch_indices(char::is_whitespace).next() {
-                    debug_assert!(i < remainder.len());
-                    // Next token might be preceded by whitespace
-                    (&remainder[..=i], &remainder[i + 1..])
-                } else {
-                    // First token without leading whitespace
-                    ("", remainder)
-                };
+            let (next_remainder, next_token) = if let Some((i, whitespace)) =
+                remainder.rmatch_indices(char::is_whitespace).next()
+            {
+                debug_assert!(i < remainder.len());
+                // Next token might be preceded by whitespace
+                let token_start = i + whitespace.len();
+                (&remainder[..token_start], &remainder[token_start..])
+            } else {
+                // First token without leading whitespace
+                ("", remainder)
+            };
             debug_assert!(!next_token.is_empty());
             debug_assert_eq!(next_token.trim(), next_token);
             if let Ok(tag) = Tag::decode_str(next_token) {
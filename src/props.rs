@@ -5,6 +5,7 @@
 
 use std::{borrow::Cow, ops::Deref};
 
+#[cfg(feature = "compact_str")]
 use compact_str::CompactString;
 
 /// Check if the given name is valid.
@@ -60,9 +61,11 @@ pub trait Name: AsRef<str> + Default + PartialEq + Sized {
 }
 
 /// A name with a `CompactString` representation
+#[cfg(feature = "compact_str")]
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CompactName(CompactString);
 
+#[cfg(feature = "compact_str")]
 impl CompactName {
     /// Create a new name.
     ///
@@ -73,12 +76,14 @@ impl CompactName {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactString> for CompactName {
     fn from(from: CompactString) -> Self {
         Self::new(from)
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl From<CompactName> for CompactString {
     fn from(from: CompactName) -> Self {
         let CompactName(inner) = from;
@@ -86,12 +91,14 @@ impl From<CompactName> for CompactString {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl AsRef<str> for CompactName {
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl Deref for CompactName {
     type Target = str;
 
@@ -100,6 +107,7 @@ impl Deref for CompactName {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl Name for CompactName {
     fn from_str(name: &str) -> Self {
         Self(name.into())
@@ -209,6 +217,7 @@ impl Value for String {
     }
 }
 
+#[cfg(feature = "compact_str")]
 impl Value for CompactString {
     fn from_str(value: &str) -> Self {
         value.into()
@@ -263,5 +272,67 @@ where
     }
 }
 
+/// Reorder properties canonically by name.
+///
+/// The sort is stable, i.e. the relative order of properties with
+/// the same name is preserved, because it might be significant.
+/// Canonically ordered properties are encoded identically, independent
+/// of their insertion order, as long as all properties with the same
+/// name have been inserted in the same relative order.
+pub fn sort_canonically<N, V>(props: &mut [Property<N, V>])
+where
+    N: Name,
+{
+    props.sort_by(|lhs, rhs| lhs.name().as_ref().cmp(rhs.name().as_ref()));
+}
+
 /// Property with a `CompactString` representation for names
+#[cfg(feature = "compact_str")]
 pub type CompactProperty<V> = Property<CompactName, V>;
+
+/// Name of the conventional property for a numeric score, e.g. a rating or confidence.
+pub const SCORE_NAME: &str = "score";
+
+/// Find and parse the value of the first [`SCORE_NAME`] property.
+///
+/// Returns `None` if the property is absent or if its value could
+/// not be parsed as a finite number.
+#[must_use]
+pub fn score<N, V>(props: &[Property<N, V>]) -> Option<f64>
+where
+    N: Name,
+    V: AsRef<str>,
+{
+    let prop = props
+        .iter()
+        .find(|prop| prop.name().as_ref() == SCORE_NAME)?;
+    prop.value()
+        .as_ref()
+        .parse::<f64>()
+        .ok()
+        .filter(|score| score.is_finite())
+}
+
+/// Set the value of the [`SCORE_NAME`] property.
+///
+/// Replaces the value of the first [`SCORE_NAME`] property or appends a
+/// new property if absent. The score must be a finite number.
+pub fn set_score<N, V>(props: &mut Vec<Property<N, V>>, score: f64)
+where
+    N: Name,
+    V: Value,
+{
+    debug_assert!(score.is_finite());
+    let value = V::from_string(score.to_string());
+    if let Some(prop) = props
+        .iter_mut()
+        .find(|prop| prop.name().as_ref() == SCORE_NAME)
+    {
+        prop.value = value;
+    } else {
+        props.push(Property {
+            name: N::from_str(SCORE_NAME),
+            value,
+        });
+    }
+}
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Streaming
+
+use std::{io, marker::PhantomData};
+
+use crate::{facet::Facet, label::Label, props, DecodeError, Tag};
+
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// Read and decode tags incrementally
+///
+/// Created by [`read_tags()`].
+#[derive(Debug)]
+pub struct ReadTags<R, F, L, N, V> {
+    reader: R,
+    buf: Vec<u8>,
+    start: usize,
+    scanned: usize,
+    eof: bool,
+    _phantom: PhantomData<Tag<F, L, N, V>>,
+}
+
+impl<R, F, L, N, V> ReadTags<R, F, L, N, V>
+where
+    R: io::Read,
+{
+    /// Classify the next character at the scanning position.
+    ///
+    /// Returns whether it is whitespace and its length in bytes, or `None`
+    /// if more input is needed. Invalid UTF-8 is not whitespace and will
+    /// be rejected when decoding the token.
+    fn scan_char(&self) -> Option<(bool, usize)> {
+        let bytes = &self.buf[self.scanned..];
+        if bytes.is_empty() {
+            return None;
+        }
+        let chunk = &bytes[..bytes.len().min(4)];
+        let valid = match std::str::from_utf8(chunk) {
+            Ok(valid) => valid,
+            Err(err) => {
+                if err.valid_up_to() == 0 {
+                    return match err.error_len() {
+                        Some(invalid_len) => Some((false, invalid_len)),
+                        // Incomplete sequence at the end of the input
+                        None if self.eof => Some((false, bytes.len())),
+                        // Incomplete sequence that might continue in the next chunk
+                        None => None,
+                    };
+                }
+                std::str::from_utf8(&chunk[..err.valid_up_to()]).unwrap_or_default()
+            }
+        };
+        valid
+            .chars()
+            .next()
+            .map(|c| (c.is_whitespace(), c.len_utf8()))
+    }
+
+    fn next_token(&mut self) -> Option<io::Result<&[u8]>> {
+        loop {
+            // Bytes between `start` and `scanned` have already been scanned
+            // and belong to the current token.
+            while let Some((is_whitespace, char_len)) = self.scan_char() {
+                if !is_whitespace {
+                    self.scanned += char_len;
+                    continue;
+                }
+                let token_start = self.start;
+                let token_end = self.scanned;
+                self.scanned += char_len;
+                self.start = self.scanned;
+                if token_start < token_end {
+                    return Some(Ok(&self.buf[token_start..token_end]));
+                }
+            }
+            if self.eof {
+                if self.start == self.buf.len() {
+                    return None;
+                }
+                // Last token without trailing whitespace
+                let token_start = self.start;
+                self.start = self.buf.len();
+                self.scanned = self.start;
+                return Some(Ok(&self.buf[token_start..]));
+            }
+            // Discard all consumed bytes and keep the beginning
+            // of a token that might continue in the next chunk.
+            self.buf.drain(..self.start);
+            self.scanned -= self.start;
+            self.start = 0;
+            let filled_len = self.buf.len();
+            self.buf.resize(filled_len + READ_CHUNK_SIZE, 0);
+            let read_result = self.reader.read(&mut self.buf[filled_len..]);
+            let read_len = *read_result.as_ref().unwrap_or(&0);
+            self.buf.truncate(filled_len + read_len);
+            match read_result {
+                Ok(0) => {
+                    self.eof = true;
+                }
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    // Abort reading after I/O errors
+                    self.eof = true;
+                    self.buf.clear();
+                    self.scanned = 0;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<R, F, L, N, V> Iterator for ReadTags<R, F, L, N, V>
+where
+    R: io::Read,
+    F: Facet,
+    L: Label,
+    N: props::Name,
+    V: props::Value,
+{
+    type Item = Result<Tag<F, L, N, V>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let decoded = match self.next_token()? {
+            Ok(token) => std::str::from_utf8(token)
+                .map_err(Into::into)
+                .and_then(Tag::decode_str),
+            Err(err) => Err(err.into()),
+        };
+        Some(decoded)
+    }
+}
+
+/// Read and decode tags incrementally from a reader.
+///
+/// The input is split into tokens that are separated by whitespace, e.g.
+/// one tag per line, like in [`DecodedTags::decode_str()`]. Each token is
+/// decoded as a single tag without loading the whole input into memory.
+/// Tokens that could not be decoded are reported as errors without aborting
+/// the stream. Reading stops after the first I/O error, which is reported
+/// as the last item.
+///
+/// Unbuffered readers don't need to be wrapped into an [`io::BufReader`],
+/// because the input is read in chunks.
+///
+/// [`DecodedTags::decode_str()`]: crate::DecodedTags::decode_str
+pub fn read_tags<R, F, L, N, V>(reader: R) -> ReadTags<R, F, L, N, V>
+where
+    R: io::Read,
+{
+    ReadTags {
+        reader,
+        buf: Vec::new(),
+        start: 0,
+        scanned: 0,
+        eof: false,
+        _phantom: PhantomData,
+    }
+}
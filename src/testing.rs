@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: The gigtags authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Fixtures for testing
+//!
+//! Reproducible facets for integration tests of downstream crates.
+//! Only available with the opt-in feature `testing`.
+
+use compact_str::format_compact;
+
+use crate::facet::{CompactFacet, FacetBuild as _};
+
+/// A curated mix of facets that cover common edge cases.
+///
+/// The order of the facets is stable.
+#[must_use]
+pub fn sample_facets() -> Vec<CompactFacet> {
+    [
+        // Plain facet with a single segment
+        "genre",
+        // Hierarchical facet with multiple segments
+        "genre/electronic/house",
+        // Dated facet with a valid calendar date
+        "played@20220625",
+        // Hierarchical, dated facet
+        "venue/berlin/club@20220625",
+        // Date-only facet with an empty prefix
+        "@20220625",
+        // Date-like suffix that doesn't encode a valid calendar date
+        "played@20220230",
+        // Whitespace within a facet is permitted
+        "mood/late night",
+        // Non-ASCII characters
+        "genre/électronique",
+        // The empty facet
+        "",
+    ]
+    .into_iter()
+    .map(CompactFacet::from_str)
+    .collect()
+}
+
+/// Generate a pseudo-random facet from a seed.
+///
+/// The same seed always results in the same, valid facet with 1 to 3
+/// segments of lowercase ASCII letters and an optional date suffix
+/// with a valid calendar date.
+#[must_use]
+pub fn random_facet(seed: u64) -> CompactFacet {
+    let mut rng = SplitMix64(seed);
+    let [segment_count, dated, year, month, day, ..] = rng.next_bytes();
+    let mut facet = compact_str::CompactString::default();
+    for segment_index in 0..=segment_count % 3 {
+        if segment_index > 0 {
+            facet.push('/');
+        }
+        let [len, letters @ ..] = rng.next_bytes();
+        for letter in letters.into_iter().take(1 + usize::from(len % 7)) {
+            facet.push(char::from(b'a' + letter % 26));
+        }
+    }
+    if dated % 2 == 0 {
+        facet.push_str(&format_compact!(
+            "@{:04}{:02}{:02}",
+            2000 + u16::from(year % 30),
+            1 + month % 12,
+            1 + day % 28,
+        ));
+    }
+    CompactFacet::new(facet)
+}
+
+// <https://prng.di.unimi.it/splitmix64.c>
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_bytes(&mut self) -> [u8; 8] {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        (z ^ (z >> 31)).to_le_bytes()
+    }
+}
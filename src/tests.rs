@@ -3,18 +3,32 @@
 
 #![allow(clippy::redundant_clone)]
 
-use compact_str::CompactString;
-
 use super::{
-    facet::{CompactFacet, Facet as _},
-    label::{CompactLabel, Label as _},
+    facet::{FacetBuild as _, FacetRef as _},
+    label::Label as _,
     *,
 };
 
-type Facet = CompactFacet;
-type Label = CompactLabel;
-type Tag = super::Tag<Facet, Label, props::CompactName, CompactString>;
-type DecodedTags = super::DecodedTags<Facet, Label, props::CompactName, CompactString>;
+#[cfg(feature = "compact_str")]
+type Facet = facet::CompactFacet;
+#[cfg(feature = "compact_str")]
+type Label = label::CompactLabel;
+#[cfg(feature = "compact_str")]
+type Name = props::CompactName;
+#[cfg(feature = "compact_str")]
+type Value = compact_str::CompactString;
+
+#[cfg(not(feature = "compact_str"))]
+type Facet = facet::StdFacet;
+#[cfg(not(feature = "compact_str"))]
+type Label = label::StdLabel;
+#[cfg(not(feature = "compact_str"))]
+type Name = props::StdName;
+#[cfg(not(feature = "compact_str"))]
+type Value = String;
+
+type Tag = super::Tag<Facet, Label, Name, Value>;
+type DecodedTags = super::DecodedTags<Facet, Label, Name, Value>;
 
 #[test]
 fn empty_tag_is_invalid() {
@@ -253,14 +267,8 @@ fn should_fail_to_decode_prop_name_with_leading_or_trailing_whitespace() {
 fn parse_from_str_allows_leading_or_trailing_whitespace() {
     assert_eq!("label", " #label".parse::<Tag>().unwrap().label().as_ref());
     assert_eq!("label", "#label ".parse::<Tag>().unwrap().label().as_ref());
-    assert_eq!(
-        "@20220625",
-        " @20220625".parse::<Tag>().unwrap().facet().as_ref()
-    );
-    assert_eq!(
-        "@20220625",
-        "@20220625 ".parse::<Tag>().unwrap().facet().as_ref()
-    );
+    assert_eq!("@20220625", &**" @20220625".parse::<Tag>().unwrap().facet());
+    assert_eq!("@20220625", &**"@20220625 ".parse::<Tag>().unwrap().facet());
 }
 
 #[test]
@@ -350,6 +358,18 @@ fn decode_and_reencode_tags_exhaustive() {
     assert_eq!("#Tag1 #Tag%202 wishlist@20220526#Someone", reencoded);
 }
 
+#[test]
+fn decode_tags_separated_by_multi_byte_whitespace() {
+    let decoded = DecodedTags::decode_str("#Tag1\u{3000}#Tag2\u{a0}wishlist@20220526");
+    assert!(decoded.undecoded_prefix.is_empty());
+    assert_eq!(3, decoded.tags.len());
+    let reencoded = decoded.reencode().unwrap();
+    assert_eq!("#Tag1 #Tag2 wishlist@20220526", reencoded);
+    let decoded = DecodedTags::decode_str("undecoded\u{3000}#Tag1");
+    assert_eq!("undecoded\u{3000}", decoded.undecoded_prefix);
+    assert_eq!(1, decoded.tags.len());
+}
+
 #[test]
 fn decode_and_reencode_tags_partially() {
     let undecoded_prefix = "This text should be preserved including the trailing newline\n";
@@ -389,3 +409,215 @@ fn reorder_and_dedup2() {
     assert!(decoded.encode_into(&mut reencoded).is_ok());
     assert_eq!(" Arbitrary comments with\twhitespace  before the first\n valid gig tag\t#first_gigtag @20220626#Label wishlist@20220625 @20220624#Label", reencoded);
 }
+
+#[test]
+fn score() {
+    let mut tag = Tag::decode_str("facet?name=val").unwrap();
+    assert_eq!(None, tag.score());
+    tag.set_score(0.75);
+    assert_eq!(Some(0.75), tag.score());
+    assert_eq!("facet?name=val&score=0.75", tag.encode());
+    tag.set_score(-1.0);
+    assert_eq!(Some(-1.0), tag.score());
+    assert_eq!(2, tag.props().len());
+    assert_eq!(
+        Some(0.5),
+        Tag::decode_str("facet?score=0.5").unwrap().score()
+    );
+    assert_eq!(None, Tag::decode_str("facet?score=high").unwrap().score());
+    assert_eq!(None, Tag::decode_str("facet?score=").unwrap().score());
+    assert_eq!(None, Tag::decode_str("facet?score=NaN").unwrap().score());
+}
+
+#[test]
+fn sort_props_canonically() {
+    let mut lhs = Tag::decode_str("facet?b=1&a=2&c=3&a=1").unwrap();
+    let mut rhs = Tag::decode_str("facet?a=2&c=3&a=1&b=1").unwrap();
+    assert_ne!(lhs.encode(), rhs.encode());
+    lhs.sort_props_canonically();
+    rhs.sort_props_canonically();
+    assert_eq!(lhs.encode(), rhs.encode());
+    // The relative order of properties with the same name is preserved
+    assert_eq!("facet?a=2&a=1&b=1&c=3", lhs.encode());
+}
+
+#[test]
+fn sort_props_canonically_preserves_order_of_duplicate_names() {
+    let mut lhs = Tag::decode_str("facet?b=1&a=1&a=2").unwrap();
+    let mut rhs = Tag::decode_str("facet?a=2&b=1&a=1").unwrap();
+    lhs.sort_props_canonically();
+    rhs.sort_props_canonically();
+    assert_eq!("facet?a=1&a=2&b=1", lhs.encode());
+    assert_eq!("facet?a=2&a=1&b=1", rhs.encode());
+}
+
+#[test]
+fn read_tags_larger_than_internal_buffer() {
+    use fmt::Write as _;
+
+    let num_tags = 2_000;
+    let mut encoded = String::new();
+    for i in 0..num_tags {
+        write!(&mut encoded, "genre/house?index={i}#Tag%20{i}").unwrap();
+        // Mix different separators
+        encoded.push_str(if i % 3 == 0 { "\n" } else { " \t " });
+    }
+    // Insert an invalid token
+    encoded.push_str("/invalid\n#Last");
+    assert!(encoded.len() > 4 * 8192);
+    let decoded = stream::read_tags(encoded.as_bytes()).collect::<Vec<Result<Tag, _>>>();
+    assert_eq!(num_tags + 2, decoded.len());
+    for (i, tag) in decoded[..num_tags].iter().enumerate() {
+        let tag = tag.as_ref().unwrap();
+        assert_eq!(format!("Tag {i}"), tag.label().as_ref());
+        assert_eq!(i.to_string(), tag.props()[0].value().as_str());
+    }
+    assert!(decoded[num_tags].is_err());
+    assert_eq!(
+        "Last",
+        decoded[num_tags + 1].as_ref().unwrap().label().as_ref()
+    );
+}
+
+#[test]
+fn read_tags_splits_tokens_like_decoded_tags() {
+    use std::io;
+
+    /// Deliver the input byte by byte to split multi-byte characters.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl io::Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    let encoded = "\u{3000}genre/house#A\u{a0}#B\u{2028}mood?x=1\t\u{85}#C\u{3000}";
+    let expected = DecodedTags::decode_str(encoded);
+    assert!(expected.undecoded_prefix.is_empty());
+    assert_eq!(4, expected.tags.len());
+    for decoded in [
+        stream::read_tags(encoded.as_bytes()).collect::<Result<Vec<Tag>, _>>(),
+        stream::read_tags(ByteReader(encoded.as_bytes())).collect::<Result<Vec<Tag>, _>>(),
+    ] {
+        assert_eq!(expected.tags, decoded.unwrap());
+    }
+}
+
+#[test]
+fn invalid_facet_error_displays_source_only_once() {
+    use std::error::Error as _;
+
+    let err = TagError::InvalidFacet(facet::FacetError::LeadingSlash);
+    assert_eq!("invalid facet", err.to_string());
+    assert_eq!(
+        facet::FacetError::LeadingSlash.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn validate_tag() {
+    let valid_tag = Tag {
+        label: Label::from_str("label"),
+        facet: Facet::from_str("facet"),
+        props: vec![Property {
+            name: props::Name::from_str("name"),
+            value: props::Value::from_str("value"),
+        }],
+    };
+    assert_eq!(Ok(()), super::validate_tag(&valid_tag));
+    assert_eq!(
+        Err(TagError::InvalidFacet(facet::FacetError::LeadingSlash)),
+        super::validate_tag(&Tag {
+            facet: Facet::from_str("/facet"),
+            ..valid_tag.clone()
+        })
+    );
+    assert_eq!(
+        Err(TagError::InvalidLabel),
+        super::validate_tag(&Tag {
+            label: Label::from_str(" label"),
+            ..valid_tag.clone()
+        })
+    );
+    assert_eq!(
+        Err(TagError::InvalidPropertyName { index: 1 }),
+        super::validate_tag(&Tag {
+            props: vec![
+                valid_tag.props[0].clone(),
+                Property {
+                    name: props::Name::from_str(""),
+                    value: props::Value::from_str("value"),
+                },
+            ],
+            ..valid_tag.clone()
+        })
+    );
+    assert_eq!(
+        Err(TagError::Incomplete),
+        super::validate_tag(&Tag::default())
+    );
+}
+
+#[test]
+fn facets_and_labels_with_identical_bytes_hash_identically() {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        hash::{Hash, Hasher},
+    };
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Facets and labels as keys of the same map need to be disambiguated.
+    #[derive(PartialEq, Eq, Hash)]
+    enum Key {
+        Facet(Facet),
+        Label(Label),
+    }
+
+    let facet = Facet::from_str("x");
+    let label = Label::from_str("x");
+    assert_eq!(hash_of(&facet), hash_of(&label));
+    assert_eq!(hash_of(&facet), hash_of(&"x"));
+
+    assert_ne!(
+        hash_of(&Key::Facet(facet.clone())),
+        hash_of(&Key::Label(label.clone()))
+    );
+    let keys = [Key::Facet(facet), Key::Label(label)]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert_eq!(2, keys.len());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn testing_fixtures() {
+    use super::testing::{random_facet, sample_facets};
+
+    let sample_facets = sample_facets();
+    assert!(sample_facets.iter().all(facet::FacetRef::is_valid));
+    assert!(sample_facets.iter().any(facet::FacetRef::is_empty));
+    assert!(sample_facets
+        .iter()
+        .any(|facet| facet.has_date_like_suffix() && facet.date_suffix().is_none()));
+
+    for seed in 0..1000 {
+        let facet = random_facet(seed);
+        assert_eq!(facet, random_facet(seed));
+        assert!(facet::validate(&facet).is_ok());
+        assert!(!facet.strip_date_like_suffix().is_empty());
+        assert_eq!(facet.has_date_like_suffix(), facet.date_suffix().is_some());
+    }
+    assert_ne!(random_facet(0), random_facet(1));
+}